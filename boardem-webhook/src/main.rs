@@ -1,9 +1,9 @@
-use std::{collections::HashMap, fmt::format};
-
-use actix_web::{get, middleware, web, App, Error as AWError, HttpResponse, HttpServer, Responder};
+use actix_web::{get, middleware, web, App, HttpResponse, HttpServer, Responder};
 use log::info;
 use newcular::{
-    board::Board,
+    board::{Board, MoveError, Square},
+    game::{Game, Outcome},
+    record::GameRecord,
     simple::{SimpleBoard, SimpleMove},
 };
 use serde::Serialize;
@@ -11,28 +11,25 @@ use std::io;
 
 #[derive(Serialize)]
 struct GameSummary {
-    valid_moves: Vec<String>,
+    valid_moves: Vec<SimpleMove>,
     render: String,
     winner: Option<i8>,
+    draw: bool,
 }
 
-fn play_board_moves(moves: &Vec<String>) -> Result<SimpleBoard, usize> {
-    let mut board = SimpleBoard::init();
+fn play_game_moves(
+    moves: &Vec<String>,
+) -> Result<Game<SimpleMove, SimpleBoard>, (usize, MoveError)> {
+    let mut game = Game::new(SimpleBoard::init());
     for (idx, mov) in moves.iter().enumerate() {
-        let moves_by_repr = board
-            .get_moves()
-            .iter()
-            .map(|&m| (m.to_string(), m))
-            .collect::<HashMap<String, SimpleMove>>();
-
-        match moves_by_repr.get(mov) {
-            Some(mov) => board.do_move(mov),
-            None => {
-                return Err(idx);
-            }
-        }
+        let mov = mov.parse::<SimpleMove>().map_err(|err| (idx, err))?;
+        game.play(&mov).map_err(|err| (idx, err))?;
     }
-    Ok(board)
+    Ok(game)
+}
+
+fn play_board_moves(moves: &Vec<String>) -> Result<SimpleBoard, (usize, MoveError)> {
+    play_game_moves(moves).map(|game| *game.board())
 }
 
 #[get("/gameType/newcular/validMoves/{moves:([A-Z0-9]+( [A-Z0-9]+)*)?}")]
@@ -44,15 +41,30 @@ async fn valid_moves(req: web::Path<(String,)>) -> impl Responder {
         .map(|s| s.to_string())
         .collect::<Vec<String>>();
     match play_board_moves(&moves) {
-        Ok(board) => HttpResponse::Ok().json(
-            board
-                .get_moves()
-                .iter()
-                .map(|&m| m.to_string())
-                .collect::<Vec<String>>(),
-        ),
-        Err(e) => {
-            return HttpResponse::BadRequest().body(format!("invalid move at index {}", e));
+        Ok(board) => HttpResponse::Ok().json(board.get_moves()),
+        Err((idx, err)) => {
+            return HttpResponse::BadRequest()
+                .body(format!("invalid move at index {}: {}", idx, err));
+        }
+    }
+}
+
+#[get("/gameType/newcular/validMovesFrom/{square}/{moves:([A-Z0-9]+( [A-Z0-9]+)*)?}")]
+async fn valid_moves_from(req: web::Path<(String, String)>) -> impl Responder {
+    let square = match req.0.parse::<Square>() {
+        Ok(square) => square,
+        Err(err) => return HttpResponse::BadRequest().body(format!("invalid square: {}", err)),
+    };
+    let moves = req
+        .1
+        .split(" ")
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>();
+    match play_board_moves(&moves) {
+        Ok(board) => HttpResponse::Ok().json(board.get_moves_from(square)),
+        Err((idx, err)) => {
+            HttpResponse::BadRequest().body(format!("invalid move at index {}: {}", idx, err))
         }
     }
 }
@@ -67,8 +79,9 @@ async fn render(req: web::Path<(String,)>) -> impl Responder {
         .collect::<Vec<String>>();
     match play_board_moves(&moves) {
         Ok(board) => HttpResponse::Ok().json(board.to_string()),
-        Err(e) => {
-            return HttpResponse::BadRequest().body(format!("invalid move at index {}", e));
+        Err((idx, err)) => {
+            return HttpResponse::BadRequest()
+                .body(format!("invalid move at index {}: {}", idx, err));
         }
     }
 }
@@ -81,18 +94,41 @@ async fn summary(req: web::Path<(String,)>) -> impl Responder {
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
         .collect::<Vec<String>>();
-    match play_board_moves(&moves) {
-        Ok(board) => HttpResponse::Ok().json(GameSummary {
-            valid_moves: board
-                .get_moves()
-                .iter()
-                .map(|&m| m.to_string())
-                .collect::<Vec<String>>(),
-            render: board.to_string(),
-            winner: board.get_winner().map(|player| player.ord()),
+    match play_game_moves(&moves) {
+        Ok(game) => HttpResponse::Ok().json(GameSummary {
+            valid_moves: game.board().get_moves(),
+            render: game.board().to_string(),
+            winner: match game.outcome() {
+                Some(Outcome::Win(player)) => Some(player.ord()),
+                _ => None,
+            },
+            draw: matches!(game.outcome(), Some(Outcome::Draw(_))),
         }),
-        Err(e) => {
-            return HttpResponse::BadRequest().body(format!("invalid move at index {}", e));
+        Err((idx, err)) => {
+            return HttpResponse::BadRequest()
+                .body(format!("invalid move at index {}: {}", idx, err));
+        }
+    }
+}
+
+#[get("/gameType/newcular/record/{moves:([A-Z0-9]+( [A-Z0-9]+)*)?}")]
+async fn record(req: web::Path<(String,)>) -> impl Responder {
+    let moves = req
+        .0
+        .split(" ")
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>();
+    match play_game_moves(&moves) {
+        Ok(game) => {
+            let mut record = GameRecord::from_game(&game);
+            record.set_tag("Event", "boardem");
+            HttpResponse::Ok()
+                .content_type("text/plain")
+                .body(record.to_string())
+        }
+        Err((idx, err)) => {
+            HttpResponse::BadRequest().body(format!("invalid move at index {}: {}", idx, err))
         }
     }
 }
@@ -108,8 +144,10 @@ async fn main() -> io::Result<()> {
         App::new()
             .wrap(middleware::Logger::default())
             .service(valid_moves)
+            .service(valid_moves_from)
             .service(render)
             .service(summary)
+            .service(record)
     })
     .bind(("127.0.0.1", 8181))?
     .workers(2)
@@ -1,50 +1,59 @@
 use minimax::{abmax::ABMax, minimax::MiniMax, EvalResult};
 use std::{
-    collections::HashMap,
     fmt::Display,
+    fs::OpenOptions,
     io::{self, stdin, Write},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use newcular::board::PieceKind;
 use newcular::{
     bitboard::BitBoard,
-    board::{Board, Mov, Player},
+    board::{Board, Mov, MoveError, Player, Square},
+    game::{Game, Outcome},
+    record::GameRecord,
     simple::SimpleBoard,
 };
 
 mod termdisplay;
 
-fn piece_rank(kind: &PieceKind) -> i32 {
-    match kind {
-        PieceKind::B => 5,
-        PieceKind::K => 50,
-        PieceKind::N => 3,
-        PieceKind::R => 5,
-        PieceKind::P => 1,
-    }
+fn eval_bitboard(b: &BitBoard) -> i32 {
+    b.material()
 }
 
-fn eval_bitboard(b: &BitBoard) -> i32 {
-    let mut total = 0i32;
-    for row_idx in 0..9 {
-        for col_idx in 0..7 {
-            total += match b.get_piece(row_idx, col_idx) {
-                Some((Player::PlayerOne, kind)) => piece_rank(&kind),
-                Some((Player::PlayerTwo, kind)) => -piece_rank(&kind),
-                None => 0i32,
-            };
-        }
+/// Finished games are appended here so they outlive the process.
+const RECORD_FILE: &str = "kled-games.txt";
+
+fn save_record<M, B>(game: &Game<M, B>)
+where
+    M: Mov + Clone,
+    B: Board<M> + Clone,
+{
+    let mut record = GameRecord::from_game(game);
+    record.set_tag("Event", "kled");
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    record.set_tag("UnixTime", &now.to_string());
+    let saved = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(RECORD_FILE)
+        .and_then(|mut file| writeln!(file, "{record}"));
+    match saved {
+        Ok(()) => println!("Game saved to {RECORD_FILE}."),
+        Err(err) => println!("Could not save game: {err}."),
     }
-    total
 }
 
 fn main() {
     play(BitBoard::init(), eval_bitboard);
 }
 
-fn play<M, B, F>(mut board: B, eval: F)
+fn play<M, B, F>(board: B, eval: F)
 where
-    M: Mov + Copy + Clone + Display + Send + 'static,
+    M: Mov + Copy + Clone + Default + Display + Send + FromStr<Err = MoveError> + 'static,
     B: Board<M> + Display + Send + Clone + 'static,
     F: Send + Clone + 'static + Fn(&B) -> i32,
 {
@@ -54,23 +63,29 @@ where
         cur_state: board.clone(),
         move_history: vec![],
     };
+    let mut game = Game::new(board);
     loop {
         term.display_all();
-        if let Some(winner) = board.get_winner() {
-            println!("Winner: {:?}", winner);
-            break;
+        match game.outcome() {
+            Some(Outcome::Win(winner)) => {
+                println!("Winner: {:?}", winner);
+                save_record(&game);
+                break;
+            }
+            Some(Outcome::Draw(reason)) => {
+                println!("Draw: {:?}", reason);
+                save_record(&game);
+                break;
+            }
+            None => {}
         }
-        let moves_by_repr = board
-            .get_moves()
-            .iter()
-            .map(|&m| (m.to_string(), m))
-            .collect::<HashMap<String, M>>();
         println!(
             "Valid moves: {}",
-            moves_by_repr
-                .keys()
-                .map(|a| a.as_str())
-                .collect::<Vec<&str>>()
+            game.board()
+                .get_moves()
+                .iter()
+                .map(|m| game.board().move_notation(m))
+                .collect::<Vec<String>>()
                 .join(", ")
         );
 
@@ -83,10 +98,11 @@ where
             //  };
             print!("Thinking...");
             let _ = io::stdout().flush();
-            let (mov, evaluation) = ABMax::<M, B, _>::choose_best_iterdeep(&board, eval.clone());
+            let (mov, evaluation) =
+                ABMax::<M, B, _>::choose_best_iterdeep_game(&game, eval.clone());
             println!(
                 "I'll play {}. {}",
-                mov,
+                game.board().move_notation(&mov),
                 match evaluation {
                     EvalResult::FavorOne(plies) if Player::PlayerOne == who_am_i =>
                         format!("I'll win in {plies}!"),
@@ -100,6 +116,7 @@ where
 
                     EvalResult::Evaluate(favor) if favor == 0 =>
                         format!("I'm feeling indifferent."),
+                    EvalResult::Draw => "I'll settle for a draw.".to_string(),
                     EvalResult::Evaluate(favor) if Player::PlayerOne == who_am_i && favor > 0 =>
                         format!("I'm feeling good!"),
                     EvalResult::Evaluate(favor) if Player::PlayerTwo == who_am_i && favor < 0 =>
@@ -107,31 +124,60 @@ where
                     _ => format!("I'm feeling bad!"),
                 }
             );
-            board.do_move(&mov);
+            game.play(&mov).unwrap();
             term.prev_state = term.cur_state.clone();
-            term.cur_state = board.clone();
+            term.cur_state = game.board().clone();
             term.move_history.push(mov);
             who_am_i = who_am_i.other();
         } else {
             loop {
-                println!("Enter a move: ");
+                println!("Enter a move, a square to list its moves, or UNDO: ");
                 let mut line = String::new();
                 if let Err(_) = stdin().read_line(&mut line) {
                     println!("Could not read move.");
                     continue;
                 }
-                match moves_by_repr.get(&line.trim().to_uppercase()) {
-                    Some(&mov) => {
-                        board.do_move(&mov);
+                if line.trim().to_uppercase() == "UNDO" {
+                    match game.undo() {
+                        Some(_) => {
+                            term.move_history.pop();
+                            term.cur_state = game.board().clone();
+                            let mut prev_game = game.clone();
+                            prev_game.undo();
+                            term.prev_state = prev_game.board().clone();
+                            break;
+                        }
+                        None => {
+                            println!("Nothing to undo.");
+                            continue;
+                        }
+                    }
+                }
+                if let Ok(square) = line.parse::<Square>() {
+                    let moves: Vec<String> = game
+                        .board()
+                        .get_moves_from(square)
+                        .iter()
+                        .map(|m| game.board().move_notation(m))
+                        .collect();
+                    match moves.is_empty() {
+                        true => println!("Nothing can move from {square}."),
+                        false => println!("Moves from {square}: {}", moves.join(", ")),
+                    }
+                    continue;
+                }
+                match game
+                    .board()
+                    .parse_move(&line)
+                    .and_then(|mov| game.play(&mov).map(|_| mov))
+                {
+                    Ok(mov) => {
                         term.prev_state = term.cur_state.clone();
-                        term.cur_state = board.clone();
+                        term.cur_state = game.board().clone();
                         term.move_history.push(mov);
                         break;
                     }
-                    None => {
-                        println!("Not a valid move.");
-                        board.get_moves();
-                    }
+                    Err(err) => println!("Not a valid move: {}.", err),
                 }
             }
         }
@@ -4,6 +4,7 @@ use newcular::board::Board;
 use newcular::board::Mov;
 use newcular::board::PieceKind;
 use newcular::board::Player;
+use newcular::board::Square;
 use newcular::simple::SimpleBoard;
 use newcular::simple::SimpleMove;
 
@@ -45,13 +46,13 @@ pub struct TermDisplay<M: Mov, B: Board<M>> {
 }
 
 impl<M: Mov, B: Board<M>> TermDisplay<M, B> {
-    fn print_char(&self, r: u8, c: u8, hl_row: u8, hl_col: u8, state: &B) {
-        let basebg = match 1u8 & (r ^ c) {
-            _ if r == hl_row && c == hl_col => ansi_term::Color::Fixed(109),
+    fn print_char(&self, square: Square, highlight: Option<Square>, state: &B) {
+        let basebg = match 1u8 & (square.row() ^ square.col()) {
+            _ if highlight == Some(square) => ansi_term::Color::Fixed(109),
             0 => ansi_term::Color::Fixed(230u8),
             _ => ansi_term::Color::Fixed(252u8),
         };
-        if let Some((player, piece_kind)) = state.get_piece(r, c) {
+        if let Some((player, piece_kind)) = state.get_piece(square) {
             let ch = match piece_kind {
                 PieceKind::B => "B",
                 PieceKind::K => "K",
@@ -83,25 +84,24 @@ impl<M: Mov, B: Board<M>> TermDisplay<M, B> {
     }
 
     fn print_board_row(&self, r: u8) {
-        let mut from_row = 200u8;
-        let mut from_col = 200u8;
-        let mut dest_row = 200u8;
-        let mut dest_col = 200u8;
+        let mut from = None;
+        let mut dest = None;
         if let Some(lastMove) = self.move_history.last() {
-            ((from_row, from_col), (dest_row, dest_col)) = lastMove.get_from_dest();
+            let (last_from, last_dest) = lastMove.get_from_dest();
+            (from, dest) = (Some(last_from), Some(last_dest));
         }
         print!(" ");
         for c in 0..7u8 {
             // textattr(RESET);
             // textattr(BRIGHT);
-            self.print_char(r, c, from_row, from_col, &self.prev_state);
+            self.print_char(Square::new(r, c).unwrap(), from, &self.prev_state);
             // textattr(RESET);
         }
         print!(" {} ", r + 1);
         for c in 0..7 {
             // textattr(RESET);
             // textattr(BRIGHT);
-            self.print_char(r, c, dest_row, dest_col, &self.cur_state);
+            self.print_char(Square::new(r, c).unwrap(), dest, &self.cur_state);
             // textattr(RESET);
         }
         print!(" ");
@@ -6,20 +6,26 @@ use std::{
 use crate::EvalResult;
 use crossbeam_channel::{after, Receiver};
 use newcular::{
-    board::{Board, Player, Mov},
+    board::{Board, Player, Mov, MoveList, Undo},
+    game::{DrawRules, Game},
 };
 
 // type EvalFn = dyn Fn(&SimpleBoard) -> i32;
 
 pub struct ABMax<M, B, F>
 where
-    M: Mov + Send + Clone,
+    M: Mov + Send,
     B: Board<M> + Clone + Send,
     F: Send,
     F: Fn(&B) -> i32,
 {
     eval: F,
     rx: Receiver<Instant>,
+    rules: DrawRules,
+    /// Hashes of the game so far and of the line being searched.
+    history: Vec<u64>,
+    /// Plies since a capture or explosion at the node being searched.
+    no_progress: u16,
 
     _phantom_move: PhantomData<M>,
     _phantom_board: PhantomData<B>,
@@ -29,29 +35,38 @@ where
 
 impl<M, B, F> ABMax<M, B, F>
 where
-    M: Mov + Send + Clone + 'static,
+    M: Mov + Send + 'static,
     B: Board<M> + Clone + Send + 'static,
     F: Send + 'static,
     F: Fn(&B) -> i32,
 {
     pub fn choose_best_iterdeep(board: &B, eval: F) -> (M, EvalResult) {
+        Self::choose_best_iterdeep_game(&Game::new(board.clone()), eval)
+    }
+
+    /// Like `choose_best_iterdeep`, but scores lines that run into the game's
+    /// draw rules as `EvalResult::Draw`.
+    pub fn choose_best_iterdeep_game(game: &Game<M, B>, eval: F) -> (M, EvalResult) {
         let (tx, rx) = crossbeam_channel::unbounded::<(M, EvalResult)>();
         let mut ab = ABMax {
             eval,
             rx: after(Duration::from_secs(5)),
+            rules: *game.rules(),
+            history: game.history().to_vec(),
+            no_progress: game.no_progress(),
             _phantom_move: PhantomData {},
             _phantom_board: PhantomData {},
         };
-        let board = board.clone();
+        let board = game.board().clone();
         thread::spawn(move || {
             let mut depth = 3;
             loop {
                 match ab.choose_best(&board, depth) {
                     // todo: short-circuit for win/loss?
                     Some(x) => {
-                        tx.send(x.clone()).unwrap();
+                        tx.send(x).unwrap();
                         match x {
-                            (_, EvalResult::Evaluate(_)) => {},
+                            (_, EvalResult::Evaluate(_) | EvalResult::Draw) => {},
                             _ => { println!("Got {depth} plies deep!"); break; },
                         }
                     },
@@ -69,18 +84,22 @@ where
         plies: u8,
     ) -> Option<(M, EvalResult)> {
         let moves = board.get_moves();
+        let mut board = board.clone();
         match board.get_player() {
             Player::PlayerOne => {
                 let mut results = vec![];
                 for m in moves {
-                    let mut board = board.clone();
-                    board.do_move(&m);
-                    match self.mini(
-                        &board,
+                    let undo = board.make_move(&m);
+                    let no_progress = self.enter(&board, &undo);
+                    let result = self.mini(
+                        &mut board,
                         EvalResult::FavorTwo(0),
                         EvalResult::FavorOne(0),
                         plies - 1,
-                    ) {
+                    );
+                    self.leave(no_progress);
+                    board.undo_move(&m, &undo);
+                    match result {
                         // todo: should we short-circuit here for win?
                         Some(x) => results.push((m, x)),
                         None => return None,
@@ -91,14 +110,17 @@ where
             Player::PlayerTwo => {
                 let mut results = vec![];
                 for m in moves {
-                    let mut board = board.clone();
-                    board.do_move(&m);
-                    match self.maxi(
-                        &board,
+                    let undo = board.make_move(&m);
+                    let no_progress = self.enter(&board, &undo);
+                    let result = self.maxi(
+                        &mut board,
                         EvalResult::FavorTwo(0),
                         EvalResult::FavorOne(0),
                         plies - 1,
-                    ) {
+                    );
+                    self.leave(no_progress);
+                    board.undo_move(&m, &undo);
+                    match result {
                         Some(x) => results.push((m, x)),
                         None => return None,
                     }
@@ -108,9 +130,32 @@ where
         }
     }
 
+    /// Records the position just reached by a move, returning the previous
+    /// no-progress count for `leave`.
+    fn enter(&mut self, board: &B, undo: &Undo) -> u16 {
+        self.history.push(board.get_hash());
+        let no_progress = self.no_progress;
+        self.no_progress = match undo.removed().next() {
+            Some(_) => 0,
+            None => no_progress.saturating_add(1),
+        };
+        no_progress
+    }
+
+    fn leave(&mut self, no_progress: u16) {
+        self.history.pop();
+        self.no_progress = no_progress;
+    }
+
+    fn is_draw(&self, board: &B) -> bool {
+        let hash = board.get_hash();
+        self.no_progress >= self.rules.no_progress_limit
+            || self.history.iter().filter(|&&seen| seen == hash).count() >= self.rules.repetitions
+    }
+
     fn maxi(
         &mut self,
-        board: &B,
+        board: &mut B,
         mut alpha: EvalResult,
         beta: EvalResult,
         plies: u8,
@@ -125,14 +170,22 @@ where
                 Player::PlayerTwo => Some(EvalResult::FavorTwo(0)),
             };
         }
+        if self.is_draw(board) {
+            return Some(EvalResult::Draw);
+        }
         if plies == 0 {
             return Some(EvalResult::Evaluate((self.eval)(board)));
         }
         let mut best = EvalResult::FavorTwo(0);
-        for m in board.get_moves() {
-            let mut board = board.clone();
-            board.do_move(&m);
-            match self.mini(&board, alpha, beta, plies - 1) {
+        let mut moves = MoveList::new();
+        board.get_moves_into(&mut moves);
+        for &m in &moves {
+            let undo = board.make_move(&m);
+            let no_progress = self.enter(board, &undo);
+            let result = self.mini(board, alpha, beta, plies - 1);
+            self.leave(no_progress);
+            board.undo_move(&m, &undo);
+            match result {
                 Some(x) => best = Ord::max(best, x.level_up()),
                 None => return None,
             }
@@ -146,7 +199,7 @@ where
 
     fn mini(
         &mut self,
-        board: &B,
+        board: &mut B,
         alpha: EvalResult,
         mut beta: EvalResult,
         plies: u8,
@@ -161,14 +214,22 @@ where
                 Player::PlayerTwo => Some(EvalResult::FavorTwo(0)),
             };
         }
+        if self.is_draw(board) {
+            return Some(EvalResult::Draw);
+        }
         if plies == 0 {
             return Some(EvalResult::Evaluate((self.eval)(board)));
         }
         let mut best = EvalResult::FavorOne(0);
-        for m in board.get_moves() {
-            let mut board = board.clone();
-            board.do_move(&m);
-            match self.maxi(&board, alpha, beta, plies - 1) {
+        let mut moves = MoveList::new();
+        board.get_moves_into(&mut moves);
+        for &m in &moves {
+            let undo = board.make_move(&m);
+            let no_progress = self.enter(board, &undo);
+            let result = self.maxi(board, alpha, beta, plies - 1);
+            self.leave(no_progress);
+            board.undo_move(&m, &undo);
+            match result {
                 Some(x) => best = Ord::min(best, x.level_up()),
                 None => return None,
             }
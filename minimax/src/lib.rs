@@ -3,10 +3,14 @@ use std::cmp::Ordering;
 pub mod minimax;
 pub mod abmax;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EvalResult {
     FavorOne(u8),
     Evaluate(i32),
+    /// Ranks equal to `Evaluate(0)`, so neither side prefers or avoids a draw
+    /// from an even position.
+    Draw,
     FavorTwo(u8),
 }
 
@@ -26,19 +30,34 @@ impl Ord for EvalResult {
             // b is "greater" if smaller (winning sooner)
             (EvalResult::FavorOne(a), EvalResult::FavorOne(b)) => b.cmp(a),
             (EvalResult::FavorOne(_), EvalResult::Evaluate(_)) => Ordering::Greater,
+            (EvalResult::FavorOne(_), EvalResult::Draw) => Ordering::Greater,
             (EvalResult::FavorOne(_), EvalResult::FavorTwo(_)) => Ordering::Greater,
 
             (EvalResult::Evaluate(_), EvalResult::FavorOne(_)) => Ordering::Less,
             (EvalResult::Evaluate(a), EvalResult::Evaluate(b)) => a.cmp(b),
+            (EvalResult::Evaluate(a), EvalResult::Draw) => a.cmp(&0),
             (EvalResult::Evaluate(_), EvalResult::FavorTwo(_)) => Ordering::Greater,
 
+            (EvalResult::Draw, EvalResult::FavorOne(_)) => Ordering::Less,
+            (EvalResult::Draw, EvalResult::Evaluate(b)) => 0.cmp(b),
+            (EvalResult::Draw, EvalResult::Draw) => Ordering::Equal,
+            (EvalResult::Draw, EvalResult::FavorTwo(_)) => Ordering::Greater,
+
             (EvalResult::FavorTwo(_), EvalResult::FavorOne(_)) => Ordering::Less,
             (EvalResult::FavorTwo(_), EvalResult::Evaluate(_)) => Ordering::Less,
+            (EvalResult::FavorTwo(_), EvalResult::Draw) => Ordering::Less,
             (EvalResult::FavorTwo(a), EvalResult::FavorTwo(b)) => a.cmp(b),
         }
     }
 }
 
+/// Equality follows `Ord`, so `Draw == Evaluate(0)`.
+impl PartialEq for EvalResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
 impl PartialOrd<EvalResult> for EvalResult {
     fn partial_cmp(&self, other: &EvalResult) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
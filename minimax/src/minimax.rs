@@ -1,6 +1,6 @@
 use crate::EvalResult;
 use newcular::{
-    board::{Board, Player},
+    board::{Board, MoveList, Player},
     simple::{SimpleBoard, SimpleMove},
 };
 
@@ -13,28 +13,22 @@ pub struct MiniMax<F> where F: Fn(&SimpleBoard) -> i32 {
 impl <F> MiniMax<F> where F: Fn(&SimpleBoard) -> i32 {
     pub fn choose_best(&self, board: &SimpleBoard, plies: u8) -> (SimpleMove, EvalResult) {
         let moves = board.get_moves();
+        let mut board = board.clone();
+        let mut results = vec![];
+        for m in moves {
+            let undo = board.make_move(&m);
+            results.push(match board.get_player() {
+                Player::PlayerOne => (m, self.maxi(&mut board, plies)),
+                Player::PlayerTwo => (m, self.mini(&mut board, plies)),
+            });
+            board.undo_move(&m, &undo);
+        }
         match board.get_player() {
-            Player::PlayerOne => moves
-                .iter()
-                .map(|&m| {
-                    let mut board = board.clone();
-                    board.do_move(&m);
-                    (m, self.mini(&board, plies))
-                })
-                .max_by_key(|(_, eval)|*eval)
-                .unwrap(),
-            Player::PlayerTwo => moves
-                .iter()
-                .map(|&m| {
-                    let mut board = board.clone();
-                    board.do_move(&m);
-                    (m, self.maxi(&board, plies))
-                })
-                .min_by_key(|(_, eval)|*eval)
-                .unwrap(),
+            Player::PlayerOne => results.into_iter().max_by_key(|(_, eval)|*eval).unwrap(),
+            Player::PlayerTwo => results.into_iter().min_by_key(|(_, eval)|*eval).unwrap(),
         }
     }
-    fn maxi(&self, board: &SimpleBoard, plies: u8) -> EvalResult {
+    fn maxi(&self, board: &mut SimpleBoard, plies: u8) -> EvalResult {
         if let Some(p) = board.get_winner() {
             return match p {
                 Player::PlayerOne => EvalResult::FavorOne(0),
@@ -44,19 +38,22 @@ impl <F> MiniMax<F> where F: Fn(&SimpleBoard) -> i32 {
         if plies == 0 {
             return EvalResult::Evaluate((self.eval)(board));
         }
-        board
-            .get_moves()
-            .iter()
-            .map(|m| {
-                let mut board = board.clone();
-                board.do_move(m);
-                return self.mini(&board, plies - 1).level_up();
-            })
-            .max()
-            .unwrap()
+        let mut best = None;
+        let mut moves = MoveList::new();
+        board.get_moves_into(&mut moves);
+        for &m in &moves {
+            let undo = board.make_move(&m);
+            let result = self.mini(board, plies - 1).level_up();
+            board.undo_move(&m, &undo);
+            best = match best {
+                Some(best) => Some(Ord::max(best, result)),
+                None => Some(result),
+            };
+        }
+        best.unwrap()
     }
 
-    fn mini(&self, board: &SimpleBoard, plies: u8) -> EvalResult {
+    fn mini(&self, board: &mut SimpleBoard, plies: u8) -> EvalResult {
         if let Some(p) = board.get_winner() {
             return match p {
                 Player::PlayerOne => EvalResult::FavorOne(0),
@@ -66,15 +63,18 @@ impl <F> MiniMax<F> where F: Fn(&SimpleBoard) -> i32 {
         if plies == 0 {
             return EvalResult::Evaluate((self.eval)(board));
         }
-        board
-            .get_moves()
-            .iter()
-            .map(|m| {
-                let mut board = board.clone();
-                board.do_move(m);
-                return self.maxi(&board, plies - 1).level_up();
-            })
-            .min()
-            .unwrap()
+        let mut best = None;
+        let mut moves = MoveList::new();
+        board.get_moves_into(&mut moves);
+        for &m in &moves {
+            let undo = board.make_move(&m);
+            let result = self.maxi(board, plies - 1).level_up();
+            board.undo_move(&m, &undo);
+            best = match best {
+                Some(best) => Some(Ord::min(best, result)),
+                None => Some(result),
+            };
+        }
+        best.unwrap()
     }
 }
@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use newcular::bitboard::BitBoard;
+use newcular::board::{Board, MoveList};
+use newcular::perft::perft;
+
+const POSITIONS: &[&str] = &[
+    "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 1",
+    "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 2",
+    "2r2n1/2k1r2/n2bB2/p3p1p/4P2/P1P1B1P/7/3b3/1NRK3 1",
+    "2rkr2/7/n6/p1p4/B4nR/P5P/4N2/1b5/1N1K3 1",
+    "1n2rn1/3Bk2/7/7/7/2r1P1P/2RB2N/3KR2/7 2",
+];
+
+fn movegen(c: &mut Criterion) {
+    let boards: Vec<BitBoard> = POSITIONS
+        .iter()
+        .map(|position| BitBoard::parse_position(position).unwrap())
+        .collect();
+
+    c.bench_function("bitboard get_moves_into", |b| {
+        let mut moves = MoveList::new();
+        b.iter(|| {
+            for board in &boards {
+                moves.clear();
+                black_box(board).get_moves_into(&mut moves);
+                black_box(&moves);
+            }
+        })
+    });
+
+    c.bench_function("bitboard perft 3", |b| {
+        b.iter(|| perft(black_box(&BitBoard::init()), 3))
+    });
+}
+
+criterion_group!(benches, movegen);
+criterion_main!(benches);
@@ -1,54 +1,105 @@
 use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
 
 use crate::board::*;
+use crate::position::{Position, PositionError};
+use crate::rules::{RuleSet, Setup};
+use crate::tables::{
+    self, EAST, NORTH, NORTH_EAST, NORTH_WEST, SOUTH, SOUTH_EAST, SOUTH_WEST, WEST,
+};
+use crate::zobrist;
 
-#[derive(Clone, Copy, Eq, Debug, PartialEq)]
+#[derive(Clone, Copy, Eq, Debug, PartialEq, Default)]
 pub struct BitBoardMove {
     from_pos: u8,
     dest_pos: u8,
 }
 
 impl BitBoardMove {
-    pub fn from_from_dest(from_pos: (u8, u8), dest_pos: (u8, u8)) -> Option<BitBoardMove> {
-        Some(BitBoardMove {
-            from_pos: 7 * from_pos.0 + from_pos.1,
-            dest_pos: 7 * dest_pos.0 + dest_pos.1,
-        })
+    pub fn from_from_dest(from: Square, dest: Square) -> BitBoardMove {
+        BitBoardMove {
+            from_pos: from.index(),
+            dest_pos: dest.index(),
+        }
+    }
+}
+
+impl FromStr for BitBoardMove {
+    type Err = MoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [from, dest] = parse_from_dest(s)?;
+        Ok(BitBoardMove::from_from_dest(from, dest))
     }
 }
 
 impl Display for BitBoardMove {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}{}{}",
-            (b'A' + self.from_pos % 7) as char,
-            (self.from_pos / 7) + 1,
-            (b'A' + self.dest_pos % 7) as char,
-            (self.dest_pos / 7) + 1
-        )
+        let (from, dest) = self.get_from_dest();
+        write!(f, "{from}{dest}")
     }
 }
 
 impl Mov for BitBoardMove {
     fn invert(&self) -> Self {
-        let from_row = self.from_pos / 7;
-        let from_col = self.from_pos % 7;
-        let dest_row = self.dest_pos / 7;
-        let dest_col = self.dest_pos % 7;
-        BitBoardMove {
-            from_pos: 7 * (8 - from_row) + from_col,
-            dest_pos: 7 * (8 - dest_row) + dest_col,
-        }
+        let (from, dest) = self.get_from_dest();
+        BitBoardMove::from_from_dest(from.invert(), dest.invert())
+    }
+    fn mirror_horizontal(&self) -> Self {
+        let (from, dest) = self.get_from_dest();
+        BitBoardMove::from_from_dest(from.mirror_horizontal(), dest.mirror_horizontal())
     }
-    fn get_from_dest(&self) -> ((u8, u8), (u8, u8)) {
+    fn get_from_dest(&self) -> (Square, Square) {
         (
-            (self.from_pos / 7, self.from_pos % 7),
-            (self.dest_pos / 7, self.dest_pos % 7),
+            Square::from_index_unchecked(self.from_pos),
+            Square::from_index_unchecked(self.dest_pos),
         )
     }
 }
 
+/// A way the masks of a `BitBoard` can disagree with each other or with the
+/// rules of the game. Squares are numbered `7 * row + col`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// A mask has a bit set above square 62.
+    OffBoard,
+    /// More than one kind mask has the square.
+    KindOverlap(u8),
+    /// `piece_mask` has the square but no kind mask does.
+    MissingKind(u8),
+    /// A kind mask has the square but `piece_mask` does not.
+    StrayKind(u8),
+    /// `player_one_mask` has the square but `piece_mask` does not.
+    OrphanedPlayer(u8),
+    TooManyKings(Player),
+    /// The hash is not the Zobrist hash of the masks.
+    StaleHash,
+    /// `material` or `piece_square` is not what the masks add up to.
+    StaleScore,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::OffBoard => write!(f, "a mask has a bit off the board"),
+            ValidationError::KindOverlap(pos) => write!(f, "square {pos} has more than one kind"),
+            ValidationError::MissingKind(pos) => {
+                write!(f, "square {pos} is occupied but has no kind")
+            }
+            ValidationError::StrayKind(pos) => write!(f, "square {pos} is empty but has a kind"),
+            ValidationError::OrphanedPlayer(pos) => {
+                write!(f, "square {pos} is empty but has an owner")
+            }
+            ValidationError::TooManyKings(player) => write!(f, "{player:?} has more than one king"),
+            ValidationError::StaleHash => write!(f, "the hash does not match the pieces"),
+            ValidationError::StaleScore => write!(f, "the scores do not match the pieces"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub struct BitBoard {
     current_player: Player,
@@ -59,11 +110,23 @@ pub struct BitBoard {
     pub knight_mask: u64,
     pub rook_mask: u64,
     pub pawn_mask: u64,
+    hash: u64,
+    /// `Board::material` and `Board::piece_square`, kept up to date like `hash`.
+    material: i32,
+    piece_square: i32,
+    rules: RuleSet,
+}
+
+impl Hash for BitBoard {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
 }
 
 impl Display for BitBoard {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let drawn = String::new();
+        let rows = Position::from_board(self).rows;
 
         for row_idx in 0..9 {
             writeln!(
@@ -72,7 +135,7 @@ impl Display for BitBoard {
                 9 - row_idx,
                 (0..7)
                     .map(|col_idx| {
-                        let piece_str = match self.get_piece(8 - row_idx, col_idx) {
+                        let piece_str = match rows[8 - row_idx][col_idx] {
                             Some((Player::PlayerOne, PieceKind::B)) => " B ",
                             Some((Player::PlayerOne, PieceKind::K)) => " K ",
                             Some((Player::PlayerOne, PieceKind::N)) => " N ",
@@ -100,26 +163,6 @@ impl Display for BitBoard {
     }
 }
 
-#[inline]
-fn nort(x: u64) -> u64 {
-    (x & 0x00ffffffffffffff) << 7
-}
-
-// #[inline]
-// fn sout(x: u64) -> u64 {
-//     (x & 0x7FFFFFFFFFFFFFFF) >> 7
-// }
-
-#[inline]
-fn east(x: u64) -> u64 {
-    (x & 0b0011111101111110111111011111101111110111111011111101111110111111) << 1
-}
-
-#[inline]
-fn west(x: u64) -> u64 {
-    (x & 0b0111111011111101111110111111011111101111110111111011111101111110) >> 1
-}
-
 #[inline]
 fn do_move_up(board: &mut u64, from_mask: u64, dest_mask: u64, up_shift: u8) {
     *board = ((*board & from_mask) << up_shift) | (*board & !from_mask & !dest_mask);
@@ -156,152 +199,165 @@ fn flip_vertical(x: u64) -> u64 {
     ((x >> 35) | (x << 35) | (x & k3)) & 0x7FFFFFFFFFFFFFFF
 }
 
-#[inline]
-fn project_fwd(state: &BitBoard, pos: u64, mask: u64, delta: u64) -> u64 {
-    let mut positions = 0u64;
-    // See https://go.mattmerr.com/bitboardhex
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) << delta;
-    positions &= !(state.piece_mask & state.player_one_mask);
-
-    positions
+/// Reflects every row of `x` across the D file.
+fn mirror_horizontal(x: u64) -> u64 {
+    const FILE_A: u64 = 0x0102040810204081;
+    let mut mirrored = 0;
+    for col in 0..7 {
+        mirrored |= ((x >> col) & FILE_A) << (6 - col);
+    }
+    mirrored
+}
+
+fn piece_material(player: Player, kind: PieceKind) -> i32 {
+    kind.value() * player.parity() as i32
+}
+
+fn piece_square(player: Player, kind: PieceKind, pos: u8) -> i32 {
+    kind.square_value(player, Square::from_index_unchecked(pos)) * player.parity() as i32
 }
 
 #[inline]
-fn project_bwd(state: &BitBoard, pos: u64, mask: u64, delta: u64) -> u64 {
-    let mut positions = 0u64;
-    // See https://go.mattmerr.com/bitboardhex
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions |= (((positions & !state.piece_mask) | pos) & mask) >> delta;
-    positions &= !(state.piece_mask & state.player_one_mask);
-
-    positions
+fn explosion_mask(pos: u8, radius: u8) -> u64 {
+    let mut mask = 1u64 << pos;
+    for _ in 0..radius.min(8) {
+        mask |= (mask & 0x00ffffffffffffffu64) << 7;
+        mask |= (mask & 0x7fffffffffffff80u64) >> 7;
+        mask |= (mask & 0x3f7efdfbf7efdfbfu64) << 1;
+        mask |= (mask & 0x7efdfbf7efdfbf7eu64) >> 1;
+    }
+    mask
 }
 
 impl BitBoard {
     pub fn init() -> Self {
-        BitBoard {
-            current_player: Player::PlayerOne,
-            piece_mask: 0b0011111000010000001000101010100000001010101000100000010000111110,
-            player_one_mask: 0b0000000000000000000000000000000000001010101000100000010000111110,
-            bishop_mask: 0b0000000000010000001000000000000000000000000000100000010000000000,
-            king_mask: 0b0000100000000000000000000000000000000000000000000000000000001000,
-            knight_mask: 0b0010001000000000000000000000000000000000000000000000000000100010,
-            rook_mask: 0b0001010000000000000000000000000000000000000000000000000000010100,
-            pawn_mask: 0b0000000000000000000000101010100000001010101000100000000000000000,
-        }
-    }
-
-    // tbh having code for either scenario seems like it would be faster
-    // fn invert(&mut self) -> Self {
-    //     self.piece_mask = flip_vertical(self.piece_mask);
-    //     self.piece_mask = flip_vertical(self.piece_mask);
-    //     self.player_one_mask = self.piece_mask;
-    // }
-
-    // fn raycast_moves(
-    //     &self,
-    //     player: &Player,
-    //     pos: (u8, u8),
-    //     del: (i8, i8),
-    // ) -> (Vec<(u8, u8)>, Option<(u8, u8)>) {
-    //     let mut cur = pos;
-    //     let mut ret = vec![];
-    //     while let Ok(nxt) = check_pos(((cur.0 as i8 + del.0) as u8, (cur.1 as i8 + del.1) as u8)) {
-    //         cur = nxt;
-    //         match self.rows[cur.0 as usize][cur.1 as usize] {
-    //             Some((other_player, _)) if *player == other_player => return (ret, None),
-    //             Some((_, _)) => {
-    //                 ret.push(cur);
-    //                 return (ret, Some(cur));
-    //             }
-    //             None => ret.push(cur),
-    //         };
-    //     }
-    //     (ret, None)
-    // }
-
-    #[inline]
-    fn get_bishop_moves(&self, pos: u64) -> u64 {
-        // See https://go.mattmerr.com/bitboardhex
-        let mut positions = 0u64;
-        // Attacks
-        positions |= project_bwd(self, pos, 0x7efdfbf7efdfbf00u64, 8);
-        positions |= project_bwd(self, pos, 0x3f7efdfbf7efdf80u64, 6);
-        positions &= self.piece_mask & !self.player_one_mask;
-
-        positions |= project_fwd(self, pos, 0x007efdfbf7efdfbfu64, 8);
-        positions |= project_fwd(self, pos, 0x00fdfbf7efdfbf7eu64, 6);
-
-        positions | pos
-    }
-
-    fn get_king_moves(&self, pos: u64) -> u64 {
-        let mut ret = 0u64;
-        let nort = nort(pos);
-        ret |= nort & !self.player_one_mask;
-        ret |= (west(nort) | east(nort)) & !(self.piece_mask & self.player_one_mask);
-        ret | pos
-    }
-
-    fn get_pawn_moves(&self, pos: u64) -> u64 {
-        let mut ret = pos;
-        let nort = nort(pos);
-        ret |= nort & !self.piece_mask;
-        ret |= (west(nort) | east(nort)) & (self.piece_mask & !self.player_one_mask);
-        ret
-    }
-
-    fn get_rook_moves(&self, pos: u64) -> u64 {
-        let mut positions = 0u64;
-
-        // Attacks
-        positions |= project_fwd(self, pos, 0x3f7efdfbf7efdfbfu64, 1);
-        positions |= project_bwd(self, pos, 0x7efdfbf7efdfbf7eu64, 1);
-        positions |= project_bwd(self, pos, 0x7fffffffffffff80u64, 7);
-        positions &= self.piece_mask & !self.player_one_mask;
-
-        positions |= project_fwd(self, pos, 0x00ffffffffffffffu64, 7);
-        positions | pos
-    }
-
-    fn get_knight_moves(&self, pos: u64) -> u64 {
-        let mut positions = 0u64;
-
-        // See https://go.mattmerr.com/bitboardhex
-        // Backwards attacks
-        positions |= (pos & 0x7cf9f3e7cf9f3e00) >> 1 * 7 + 2;
-        positions |= (pos & 0x1f3e7cf9f3e7cf80) >> 1 * 7 - 2;
-        positions |= (pos & 0x7efdfbf7efdf8000) >> 2 * 7 + 1;
-        positions |= (pos & 0x3f7efdfbf7efc000) >> 2 * 7 - 1;
-        positions &= self.piece_mask & !self.player_one_mask;
-
-        // Forward moves
-        positions |= (pos & 0x003e7cf9f3e7cf9f) << 1 * 7 + 2;
-        positions |= (pos & 0x00f9f3e7cf9f3e7c) << 1 * 7 - 2;
-        positions |= (pos & 0x0000fdfbf7efdfbf) << 2 * 7 + 1;
-        positions |= (pos & 0x0001fbf7efdfbf7e) << 2 * 7 - 1;
-        positions &= !(self.piece_mask & self.player_one_mask);
-
-        positions | pos
+        Self::from_setup(&Setup::default())
     }
-}
 
-impl Board<BitBoardMove> for BitBoard {
-    fn get_piece(&self, row_idx: u8, col_idx: u8) -> Option<(Player, PieceKind)> {
-        let hot_bit = 1u64 << (row_idx * 7 + col_idx);
+    pub fn from_position(position: &Position) -> Self {
+        Self::from_setup(&Setup {
+            position: *position,
+            rules: RuleSet::default(),
+        })
+    }
+
+    pub fn from_setup(setup: &Setup) -> Self {
+        let position = &setup.position;
+        let mut board = BitBoard {
+            current_player: position.player,
+            piece_mask: 0,
+            player_one_mask: 0,
+            bishop_mask: 0,
+            king_mask: 0,
+            knight_mask: 0,
+            rook_mask: 0,
+            pawn_mask: 0,
+            hash: zobrist::player_key(position.player),
+            material: 0,
+            piece_square: 0,
+            rules: setup.rules,
+        };
+        for (pos, square) in position.rows.iter().flatten().enumerate() {
+            if let Some((player, kind)) = square {
+                board.put_piece(pos as u8, *player, *kind);
+            }
+        }
+        debug_assert_eq!(board.validate(), Ok(()));
+        board
+    }
+
+    pub fn parse_position(s: &str) -> Result<Self, PositionError> {
+        Ok(Self::from_position(&s.parse()?))
+    }
+
+    /// Checks that the masks agree with each other, and that the hash and
+    /// scores match them.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_masks()?;
+        if self.hash != self.compute_hash() {
+            return Err(ValidationError::StaleHash);
+        }
+        match self.compute_scores() == (self.material, self.piece_square) {
+            true => Ok(()),
+            false => Err(ValidationError::StaleScore),
+        }
+    }
+
+    /// Builds a board from the seven masks, in field order, under the default
+    /// rules. The masks are validated before anything is derived from them.
+    pub(crate) fn from_masks(player: Player, masks: [u64; 7]) -> Result<Self, ValidationError> {
+        let [piece_mask, player_one_mask, bishop_mask, king_mask, knight_mask, rook_mask, pawn_mask] =
+            masks;
+        let mut board = BitBoard {
+            current_player: player,
+            piece_mask,
+            player_one_mask,
+            bishop_mask,
+            king_mask,
+            knight_mask,
+            rook_mask,
+            pawn_mask,
+            hash: 0,
+            material: 0,
+            piece_square: 0,
+            rules: RuleSet::default(),
+        };
+        board.validate_masks()?;
+        board.hash = board.compute_hash();
+        (board.material, board.piece_square) = board.compute_scores();
+        Ok(board)
+    }
+
+    /// The checks of `validate` that only look at the masks.
+    pub(crate) fn validate_masks(&self) -> Result<(), ValidationError> {
+        let first = |mask: u64| mask.trailing_zeros() as u8;
+        let kinds = [
+            self.bishop_mask,
+            self.king_mask,
+            self.knight_mask,
+            self.rook_mask,
+            self.pawn_mask,
+        ];
+        if kinds
+            .iter()
+            .chain([&self.piece_mask, &self.player_one_mask])
+            .any(|mask| mask >> 63 != 0)
+        {
+            return Err(ValidationError::OffBoard);
+        }
+        let mut seen = 0u64;
+        for kind in kinds {
+            if seen & kind != 0 {
+                return Err(ValidationError::KindOverlap(first(seen & kind)));
+            }
+            seen |= kind;
+        }
+        if self.piece_mask & !seen != 0 {
+            return Err(ValidationError::MissingKind(first(self.piece_mask & !seen)));
+        }
+        if seen & !self.piece_mask != 0 {
+            return Err(ValidationError::StrayKind(first(seen & !self.piece_mask)));
+        }
+        if self.player_one_mask & !self.piece_mask != 0 {
+            return Err(ValidationError::OrphanedPlayer(first(
+                self.player_one_mask & !self.piece_mask,
+            )));
+        }
+        if (self.king_mask & self.player_one_mask).count_ones() > 1 {
+            return Err(ValidationError::TooManyKings(Player::PlayerOne));
+        }
+        if (self.king_mask & !self.player_one_mask).count_ones() > 1 {
+            return Err(ValidationError::TooManyKings(Player::PlayerTwo));
+        }
+        Ok(())
+    }
+
+    pub fn to_position(&self) -> String {
+        Position::from_board(self).to_string()
+    }
+
+    fn piece_at(&self, pos: u8) -> Option<(Player, PieceKind)> {
+        let hot_bit = 1u64 << pos;
         if (self.piece_mask & hot_bit) == 0 {
             return None;
         }
@@ -316,43 +372,455 @@ impl Board<BitBoardMove> for BitBoard {
                 _ if (self.knight_mask & hot_bit) != 0 => PieceKind::N,
                 _ if (self.pawn_mask & hot_bit) != 0 => PieceKind::P,
                 _ if (self.rook_mask & hot_bit) != 0 => PieceKind::R,
-                _ => unimplemented!(),
+                // A valid board has a kind for every occupied square.
+                _ => unreachable!(),
             },
         ))
     }
+
+    /// Zobrist key of every piece in `mask`.
+    fn hash_pieces(&self, mask: u64) -> u64 {
+        let mut remaining = mask & self.piece_mask;
+        let mut hash = 0u64;
+        while remaining > 0 {
+            let pos = remaining.trailing_zeros() as u8;
+            if let Some((player, kind)) = self.piece_at(pos) {
+                hash ^= zobrist::piece_key(player, kind, pos);
+            }
+            remaining ^= 1u64 << pos;
+        }
+        hash
+    }
+
+    fn put_piece(&mut self, pos: u8, player: Player, kind: PieceKind) {
+        let hot_bit = 1u64 << pos;
+        self.piece_mask |= hot_bit;
+        if player == Player::PlayerOne {
+            self.player_one_mask |= hot_bit;
+        }
+        match kind {
+            PieceKind::B => self.bishop_mask |= hot_bit,
+            PieceKind::K => self.king_mask |= hot_bit,
+            PieceKind::N => self.knight_mask |= hot_bit,
+            PieceKind::R => self.rook_mask |= hot_bit,
+            PieceKind::P => self.pawn_mask |= hot_bit,
+        }
+        self.hash ^= zobrist::piece_key(player, kind, pos);
+        self.material += piece_material(player, kind);
+        self.piece_square += piece_square(player, kind, pos);
+    }
+
+    /// Takes every piece in `mask` out of `material` and `piece_square`.
+    fn unscore_pieces(&mut self, mask: u64) {
+        let mut remaining = mask & self.piece_mask;
+        while remaining > 0 {
+            let pos = remaining.trailing_zeros() as u8;
+            if let Some((player, kind)) = self.piece_at(pos) {
+                self.material -= piece_material(player, kind);
+                self.piece_square -= piece_square(player, kind, pos);
+            }
+            remaining ^= 1u64 << pos;
+        }
+    }
+
+    /// Updates the scores for moving (and possibly capturing with) a piece.
+    fn score_displacement(&mut self, from_pos: u8, dest_pos: u8) {
+        if let Some((player, kind)) = self.piece_at(from_pos) {
+            self.piece_square +=
+                piece_square(player, kind, dest_pos) - piece_square(player, kind, from_pos);
+        }
+        if let Some((player, kind)) = self.piece_at(dest_pos) {
+            self.material -= piece_material(player, kind);
+            self.piece_square -= piece_square(player, kind, dest_pos);
+        }
+    }
+
+    /// Moves whatever is on `from_pos` to `dest_pos` in every mask, clearing `dest_pos` first.
+    fn shift_piece(&mut self, from_pos: u8, dest_pos: u8) {
+        let from_mask = 1u64 << from_pos;
+        let dest_mask = 1u64 << dest_pos;
+        if from_pos < dest_pos {
+            let up_shift = dest_pos - from_pos;
+            do_move_up(&mut self.piece_mask, from_mask, dest_mask, up_shift);
+            do_move_up(&mut self.player_one_mask, from_mask, dest_mask, up_shift);
+            do_move_up(&mut self.bishop_mask, from_mask, dest_mask, up_shift);
+            do_move_up(&mut self.king_mask, from_mask, dest_mask, up_shift);
+            do_move_up(&mut self.knight_mask, from_mask, dest_mask, up_shift);
+            do_move_up(&mut self.pawn_mask, from_mask, dest_mask, up_shift);
+            do_move_up(&mut self.rook_mask, from_mask, dest_mask, up_shift);
+        } else {
+            let down_shift = from_pos - dest_pos;
+            do_move_down(&mut self.piece_mask, from_mask, dest_mask, down_shift);
+            do_move_down(&mut self.player_one_mask, from_mask, dest_mask, down_shift);
+            do_move_down(&mut self.bishop_mask, from_mask, dest_mask, down_shift);
+            do_move_down(&mut self.king_mask, from_mask, dest_mask, down_shift);
+            do_move_down(&mut self.knight_mask, from_mask, dest_mask, down_shift);
+            do_move_down(&mut self.pawn_mask, from_mask, dest_mask, down_shift);
+            do_move_down(&mut self.rook_mask, from_mask, dest_mask, down_shift);
+        }
+    }
+
+    /// Change in Zobrist key from moving (and possibly capturing with) a piece.
+    fn hash_displacement(&self, mov: &BitBoardMove) -> u64 {
+        let mut hash = self.hash_pieces((1u64 << mov.from_pos) | (1u64 << mov.dest_pos));
+        if let Some((player, kind)) = self.piece_at(mov.from_pos) {
+            hash ^= zobrist::piece_key(player, kind, mov.dest_pos);
+        }
+        hash
+    }
+
+    /// `material` and `piece_square` counted from scratch.
+    fn compute_scores(&self) -> (i32, i32) {
+        let mut scores = (0, 0);
+        for (square, player, kind) in self.pieces() {
+            scores.0 += piece_material(player, kind);
+            scores.1 += piece_square(player, kind, square.index());
+        }
+        scores
+    }
+
+    fn compute_hash(&self) -> u64 {
+        self.hash_pieces(self.piece_mask) ^ zobrist::player_key(self.current_player)
+    }
+
+    /// The board seen from the other side, for `invert`, which then recomputes
+    /// the hash this leaves at zero.
+    fn flip(&self) -> BitBoard {
+        BitBoard {
+            current_player: self.current_player.other(),
+            piece_mask: flip_vertical(self.piece_mask),
+            player_one_mask: flip_vertical(self.piece_mask ^ self.player_one_mask),
+            bishop_mask: flip_vertical(self.bishop_mask),
+            king_mask: flip_vertical(self.king_mask),
+            knight_mask: flip_vertical(self.knight_mask),
+            rook_mask: flip_vertical(self.rook_mask),
+            pawn_mask: flip_vertical(self.pawn_mask),
+            hash: 0,
+            material: -self.material,
+            piece_square: -self.piece_square,
+            rules: self.rules,
+        }
+    }
+
+    // Each generator takes the square of a piece belonging to the side the
+    // generator is for. Backward directions can only capture.
+
+    fn get_bishop_moves(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & self.player_one_mask;
+        let enemy = self.piece_mask & !self.player_one_mask;
+        let occupied = self.piece_mask;
+        let attacks = tables::ray(SOUTH_EAST, from_pos, occupied)
+            | tables::ray(SOUTH_WEST, from_pos, occupied);
+        let moves = tables::ray(NORTH_EAST, from_pos, occupied)
+            | tables::ray(NORTH_WEST, from_pos, occupied);
+        (attacks & enemy) | (moves & !own) | (1u64 << from_pos)
+    }
+
+    fn get_king_moves(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & self.player_one_mask;
+        (tables::KING_UP[from_pos as usize] & !own) | (1u64 << from_pos)
+    }
+
+    fn get_pawn_moves(&self, from_pos: u8) -> u64 {
+        let enemy = self.piece_mask & !self.player_one_mask;
+        let ahead = tables::KING_UP[from_pos as usize] & ((1u64 << from_pos) << 7);
+        let captures = tables::KING_UP[from_pos as usize] & !ahead;
+        (ahead & !self.piece_mask) | (captures & enemy) | (1u64 << from_pos)
+    }
+
+    fn get_rook_moves(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & self.player_one_mask;
+        let enemy = self.piece_mask & !self.player_one_mask;
+        let occupied = self.piece_mask;
+        let attacks = tables::ray(EAST, from_pos, occupied)
+            | tables::ray(WEST, from_pos, occupied)
+            | tables::ray(SOUTH, from_pos, occupied);
+        let moves = tables::ray(NORTH, from_pos, occupied);
+        (attacks & enemy) | (moves & !own) | (1u64 << from_pos)
+    }
+
+    fn get_knight_moves(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & self.player_one_mask;
+        let enemy = self.piece_mask & !self.player_one_mask;
+        (tables::KNIGHT_DOWN[from_pos as usize] & enemy)
+            | (tables::KNIGHT_UP[from_pos as usize] & !own)
+            | (1u64 << from_pos)
+    }
+
+    // PlayerTwo moves down the board, so its forward and backward directions
+    // swap relative to the generators above.
+
+    fn get_bishop_moves_down(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & !self.player_one_mask;
+        let enemy = self.piece_mask & self.player_one_mask;
+        let occupied = self.piece_mask;
+        let attacks = tables::ray(NORTH_EAST, from_pos, occupied)
+            | tables::ray(NORTH_WEST, from_pos, occupied);
+        let moves = tables::ray(SOUTH_EAST, from_pos, occupied)
+            | tables::ray(SOUTH_WEST, from_pos, occupied);
+        (attacks & enemy) | (moves & !own) | (1u64 << from_pos)
+    }
+
+    fn get_king_moves_down(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & !self.player_one_mask;
+        (tables::KING_DOWN[from_pos as usize] & !own) | (1u64 << from_pos)
+    }
+
+    fn get_pawn_moves_down(&self, from_pos: u8) -> u64 {
+        let enemy = self.piece_mask & self.player_one_mask;
+        let ahead = (1u64 << from_pos) >> 7;
+        let captures = tables::KING_DOWN[from_pos as usize] & !ahead;
+        (ahead & !self.piece_mask) | (captures & enemy) | (1u64 << from_pos)
+    }
+
+    fn get_rook_moves_down(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & !self.player_one_mask;
+        let enemy = self.piece_mask & self.player_one_mask;
+        let occupied = self.piece_mask;
+        let attacks = tables::ray(EAST, from_pos, occupied)
+            | tables::ray(WEST, from_pos, occupied)
+            | tables::ray(NORTH, from_pos, occupied);
+        let moves = tables::ray(SOUTH, from_pos, occupied);
+        (attacks & enemy) | (moves & !own) | (1u64 << from_pos)
+    }
+
+    fn get_knight_moves_down(&self, from_pos: u8) -> u64 {
+        let own = self.piece_mask & !self.player_one_mask;
+        let enemy = self.piece_mask & self.player_one_mask;
+        (tables::KNIGHT_UP[from_pos as usize] & enemy)
+            | (tables::KNIGHT_DOWN[from_pos as usize] & !own)
+            | (1u64 << from_pos)
+    }
+
+    fn player_mask(&self, player: Player) -> u64 {
+        match player {
+            Player::PlayerOne => self.piece_mask & self.player_one_mask,
+            Player::PlayerTwo => self.piece_mask & !self.player_one_mask,
+        }
+    }
+
+    /// Pieces belonging to the side to move.
+    fn own_mask(&self) -> u64 {
+        self.player_mask(self.current_player)
+    }
+
+    /// Destinations of the piece on `from_pos`, which belongs to the side to move.
+    fn get_piece_moves(&self, from_pos: u8) -> u64 {
+        let from_mask = 1u64 << from_pos;
+        let Some((player, kind)) = self.piece_at(from_pos) else {
+            unreachable!("no piece on square {from_pos}");
+        };
+        let mut dest_positions = match (player, kind) {
+            (Player::PlayerOne, PieceKind::B) => self.get_bishop_moves(from_pos),
+            (Player::PlayerOne, PieceKind::K) => self.get_king_moves(from_pos),
+            (Player::PlayerOne, PieceKind::N) => self.get_knight_moves(from_pos),
+            (Player::PlayerOne, PieceKind::P) => self.get_pawn_moves(from_pos),
+            (Player::PlayerOne, PieceKind::R) => self.get_rook_moves(from_pos),
+            (Player::PlayerTwo, PieceKind::B) => self.get_bishop_moves_down(from_pos),
+            (Player::PlayerTwo, PieceKind::K) => self.get_king_moves_down(from_pos),
+            (Player::PlayerTwo, PieceKind::N) => self.get_knight_moves_down(from_pos),
+            (Player::PlayerTwo, PieceKind::P) => self.get_pawn_moves_down(from_pos),
+            (Player::PlayerTwo, PieceKind::R) => self.get_rook_moves_down(from_pos),
+        };
+        let blast = explosion_mask(from_pos, self.rules.explosion_radius);
+        if !self.rules.self_destruct_own_king && blast & self.king_mask & self.own_mask() != 0 {
+            dest_positions &= !from_mask;
+        }
+        dest_positions
+    }
+
+    /// Squares a `player` piece on `from_pos` could capture on, were an
+    /// enemy piece standing there.
+    fn get_attacks(&self, from_pos: u8, player: Player) -> u64 {
+        let pos = 1u64 << from_pos;
+        let occupied = self.piece_mask;
+        let (forward, ahead) = match player {
+            Player::PlayerOne => (tables::KING_UP[from_pos as usize], pos << 7),
+            Player::PlayerTwo => (tables::KING_DOWN[from_pos as usize], pos >> 7),
+        };
+        let attacks = match 1 {
+            _ if (self.bishop_mask & pos) != 0 => {
+                tables::ray(NORTH_EAST, from_pos, occupied)
+                    | tables::ray(NORTH_WEST, from_pos, occupied)
+                    | tables::ray(SOUTH_EAST, from_pos, occupied)
+                    | tables::ray(SOUTH_WEST, from_pos, occupied)
+            }
+            _ if (self.king_mask & pos) != 0 => forward,
+            _ if (self.knight_mask & pos) != 0 => {
+                tables::KNIGHT_UP[from_pos as usize] | tables::KNIGHT_DOWN[from_pos as usize]
+            }
+            _ if (self.pawn_mask & pos) != 0 => forward & !ahead,
+            _ if (self.rook_mask & pos) != 0 => {
+                tables::ray(NORTH, from_pos, occupied)
+                    | tables::ray(SOUTH, from_pos, occupied)
+                    | tables::ray(EAST, from_pos, occupied)
+                    | tables::ray(WEST, from_pos, occupied)
+            }
+            _ => 0,
+        };
+        attacks & !self.player_mask(player)
+    }
+
+    /// Squares not occupied by `player` on which one of `player`'s pieces
+    /// could capture an enemy piece. Self-destructs are not counted; see
+    /// `explosion_threats`.
+    pub fn attacks(&self, player: Player) -> u64 {
+        let mut unconsidered = self.player_mask(player);
+        let mut attacks = 0u64;
+        while unconsidered > 0 {
+            let from_pos = unconsidered.trailing_zeros() as u8;
+            attacks |= self.get_attacks(from_pos, player);
+            unconsidered ^= 1u64 << from_pos;
+        }
+        attacks
+    }
+
+    /// `player`'s pieces that could capture on `square`.
+    pub fn attackers(&self, square: Square, player: Player) -> u64 {
+        let target = square.bit();
+        let mut unconsidered = self.player_mask(player);
+        let mut attackers = 0u64;
+        while unconsidered > 0 {
+            let from_pos = unconsidered.trailing_zeros() as u8;
+            if self.get_attacks(from_pos, player) & target != 0 {
+                attackers |= 1u64 << from_pos;
+            }
+            unconsidered ^= 1u64 << from_pos;
+        }
+        attackers
+    }
+
+    /// Enemy pieces that could capture the piece on `square`, if there is one.
+    pub fn threats_to(&self, square: Square) -> u64 {
+        match self.get_piece(square) {
+            Some((player, _)) => self.attackers(square, player.other()),
+            None => 0,
+        }
+    }
+
+    /// `player`'s pieces that the other side could blow up with a self-destruct.
+    pub fn explosion_threats(&self, player: Player) -> u64 {
+        let own = self.player_mask(player);
+        let enemy = self.piece_mask & !own;
+        let mut unconsidered = enemy;
+        let mut threatened = 0u64;
+        while unconsidered > 0 {
+            let pos = unconsidered.trailing_zeros() as u8;
+            let blast = explosion_mask(pos, self.rules.explosion_radius);
+            if self.rules.self_destruct_own_king || blast & enemy & self.king_mask == 0 {
+                threatened |= blast;
+            }
+            unconsidered ^= 1u64 << pos;
+        }
+        threatened & own
+    }
+}
+
+impl Board<BitBoardMove> for BitBoard {
+    fn get_piece(&self, square: Square) -> Option<(Player, PieceKind)> {
+        self.piece_at(square.index())
+    }
+
+    fn pieces(&self) -> impl Iterator<Item = (Square, Player, PieceKind)> + '_ {
+        let mut remaining = self.piece_mask;
+        std::iter::from_fn(move || {
+            let pos = remaining.trailing_zeros() as u8;
+            remaining &= remaining.checked_sub(1)?;
+            let (player, kind) = self.piece_at(pos)?;
+            Some((Square::from_index_unchecked(pos), player, kind))
+        })
+    }
+
+    fn piece_counts(&self, player: Player) -> PieceCounts {
+        let own = self.player_mask(player);
+        let mut counts = PieceCounts::default();
+        for (kind, mask) in [
+            (PieceKind::B, self.bishop_mask),
+            (PieceKind::K, self.king_mask),
+            (PieceKind::N, self.knight_mask),
+            (PieceKind::R, self.rook_mask),
+            (PieceKind::P, self.pawn_mask),
+        ] {
+            counts.add(kind, (mask & own).count_ones() as u8);
+        }
+        counts
+    }
+
+    fn king_square(&self, player: Player) -> Option<Square> {
+        let king = self.king_mask & self.player_mask(player);
+        (king != 0).then(|| Square::from_index_unchecked(king.trailing_zeros() as u8))
+    }
+
+    fn preview_explosion(&self, square: Square) -> Option<ExplosionPreview> {
+        let pos = square.index();
+        self.piece_at(pos)?;
+        let squares = explosion_mask(pos, self.rules.explosion_radius);
+        let mut destroyed = vec![];
+        let mut remaining = squares & self.piece_mask;
+        while remaining > 0 {
+            let pos = remaining.trailing_zeros() as u8;
+            if let Some((player, kind)) = self.piece_at(pos) {
+                destroyed.push((Square::from_index_unchecked(pos), player, kind));
+            }
+            remaining ^= 1u64 << pos;
+        }
+        Some(ExplosionPreview { squares, destroyed })
+    }
     fn get_player(&self) -> Player {
         self.current_player
     }
+    fn get_rules(&self) -> RuleSet {
+        self.rules
+    }
+    fn get_hash(&self) -> u64 {
+        self.hash
+    }
 
     fn get_moves(&self) -> Vec<BitBoardMove> {
-        if self.current_player != Player::PlayerOne {
-            let inv = self.invert();
-            return inv.get_moves().into_iter().map(|m| m.invert()).collect();
-        }
-        let mut unconsidered = self.piece_mask & self.player_one_mask;
-        let mut ret = vec![];
+        let mut moves = MoveList::new();
+        self.get_moves_into(&mut moves);
+        moves.to_vec()
+    }
+
+    fn get_moves_into(&self, moves: &mut MoveList<BitBoardMove>) {
+        let mut unconsidered = self.own_mask();
         while unconsidered > 0 {
             let from_pos = unconsidered.trailing_zeros() as u8;
-            let from_mask = 1u64 << from_pos;
-
-            let mut dest_positions = match 1 {
-                _ if (self.bishop_mask & from_mask) != 0 => self.get_bishop_moves(from_mask),
-                _ if (self.king_mask & from_mask) != 0 => self.get_king_moves(from_mask),
-                _ if (self.knight_mask & from_mask) != 0 => self.get_knight_moves(from_mask),
-                _ if (self.pawn_mask & from_mask) != 0 => self.get_pawn_moves(from_mask),
-                _ if (self.rook_mask & from_mask) != 0 => self.get_rook_moves(from_mask),
-                _ => unimplemented!(),
-            };
-
+            let mut dest_positions = self.get_piece_moves(from_pos);
             while dest_positions > 0 {
                 let dest_pos = dest_positions.trailing_zeros() as u8;
-                ret.push(BitBoardMove { from_pos, dest_pos });
+                moves.push(BitBoardMove { from_pos, dest_pos });
                 dest_positions ^= 1u64 << dest_pos;
             }
+            unconsidered ^= 1u64 << from_pos;
+        }
+    }
 
-            unconsidered ^= from_mask;
+    fn get_moves_from(&self, square: Square) -> Vec<BitBoardMove> {
+        let from_pos = square.index();
+        if self.own_mask() & square.bit() == 0 {
+            return vec![];
         }
-        ret
+        let mut moves = vec![];
+        let mut dest_positions = self.get_piece_moves(from_pos);
+        while dest_positions > 0 {
+            let dest_pos = dest_positions.trailing_zeros() as u8;
+            moves.push(BitBoardMove { from_pos, dest_pos });
+            dest_positions ^= 1u64 << dest_pos;
+        }
+        moves
+    }
+
+    fn has_any_move(&self) -> bool {
+        let mut unconsidered = self.own_mask();
+        while unconsidered > 0 {
+            let from_pos = unconsidered.trailing_zeros() as u8;
+            if self.get_piece_moves(from_pos) != 0 {
+                return true;
+            }
+            unconsidered ^= 1u64 << from_pos;
+        }
+        false
     }
 
     fn get_winner(&self) -> Option<Player> {
@@ -365,20 +833,26 @@ impl Board<BitBoardMove> for BitBoard {
         if (self.piece_mask & !self.player_one_mask & self.king_mask) == 0 {
             return Some(Player::PlayerOne);
         }
-        if self.get_moves().is_empty() {
+        if !self.has_any_move() {
             return Some(self.current_player.other());
         }
         None
     }
 
+    fn material(&self) -> i32 {
+        self.material
+    }
+
+    fn piece_square(&self) -> i32 {
+        self.piece_square
+    }
+
     fn do_move(&mut self, mov: &BitBoardMove) {
         if mov.from_pos == mov.dest_pos {
             // Explode!!
-            let mut mask = 1u64 << mov.from_pos;
-            mask |= (mask & 0x00ffffffffffffffu64) << 7;
-            mask |= (mask & 0x7fffffffffffff80u64) >> 7;
-            mask |= (mask & 0x3f7efdfbf7efdfbfu64) << 1;
-            mask |= (mask & 0x7efdfbf7efdfbf7eu64) >> 1;
+            let mask = explosion_mask(mov.from_pos, self.rules.explosion_radius);
+            self.hash ^= self.hash_pieces(mask);
+            self.unscore_pieces(mask);
             self.piece_mask &= !mask;
             self.player_one_mask &= !mask;
             self.bishop_mask &= !mask;
@@ -386,49 +860,78 @@ impl Board<BitBoardMove> for BitBoard {
             self.knight_mask &= !mask;
             self.pawn_mask &= !mask;
             self.rook_mask &= !mask;
-        } else if mov.from_pos < mov.dest_pos {
-            let from_mask = 1u64 << mov.from_pos;
-            let dest_mask = 1u64 << mov.dest_pos;
-            let up_shift = mov.dest_pos - mov.from_pos;
-            do_move_up(&mut self.piece_mask, from_mask, dest_mask, up_shift);
-            do_move_up(&mut self.player_one_mask, from_mask, dest_mask, up_shift);
-            do_move_up(&mut self.bishop_mask, from_mask, dest_mask, up_shift);
-            do_move_up(&mut self.king_mask, from_mask, dest_mask, up_shift);
-            do_move_up(&mut self.knight_mask, from_mask, dest_mask, up_shift);
-            do_move_up(&mut self.pawn_mask, from_mask, dest_mask, up_shift);
-            do_move_up(&mut self.rook_mask, from_mask, dest_mask, up_shift);
         } else {
-            let from_mask = 1u64 << mov.from_pos;
-            let dest_mask = 1u64 << mov.dest_pos;
-            let down_shift = mov.from_pos - mov.dest_pos;
-            do_move_down(&mut self.piece_mask, from_mask, dest_mask, down_shift);
-            do_move_down(&mut self.player_one_mask, from_mask, dest_mask, down_shift);
-            do_move_down(&mut self.bishop_mask, from_mask, dest_mask, down_shift);
-            do_move_down(&mut self.king_mask, from_mask, dest_mask, down_shift);
-            do_move_down(&mut self.knight_mask, from_mask, dest_mask, down_shift);
-            do_move_down(&mut self.pawn_mask, from_mask, dest_mask, down_shift);
-            do_move_down(&mut self.rook_mask, from_mask, dest_mask, down_shift);
+            self.hash ^= self.hash_displacement(mov);
+            self.score_displacement(mov.from_pos, mov.dest_pos);
+            self.shift_piece(mov.from_pos, mov.dest_pos);
+        }
+        self.current_player = self.current_player.other();
+        self.hash ^= zobrist::PLAYER_TWO_KEY;
+        debug_assert_eq!(self.validate(), Ok(()), "after {mov}");
+    }
+
+    fn make_move(&mut self, mov: &BitBoardMove) -> Undo {
+        let mut undo = Undo::default();
+        let mut removed = match mov.from_pos == mov.dest_pos {
+            true => explosion_mask(mov.from_pos, self.rules.explosion_radius) & self.piece_mask,
+            false => (1u64 << mov.dest_pos) & self.piece_mask,
+        };
+        while removed > 0 {
+            let pos = removed.trailing_zeros() as u8;
+            if let Some((player, kind)) = self.piece_at(pos) {
+                undo.push(pos, player, kind);
+            }
+            removed ^= 1u64 << pos;
         }
+        self.do_move(mov);
+        undo
+    }
+
+    fn undo_move(&mut self, mov: &BitBoardMove, undo: &Undo) {
         self.current_player = self.current_player.other();
+        self.hash ^= zobrist::PLAYER_TWO_KEY;
+        if mov.from_pos != mov.dest_pos {
+            let back = BitBoardMove {
+                from_pos: mov.dest_pos,
+                dest_pos: mov.from_pos,
+            };
+            self.hash ^= self.hash_displacement(&back);
+            self.score_displacement(back.from_pos, back.dest_pos);
+            self.shift_piece(back.from_pos, back.dest_pos);
+        }
+        for (pos, player, kind) in undo.removed() {
+            self.put_piece(pos, player, kind);
+        }
     }
 
     fn invert(&self) -> BitBoard {
-        return BitBoard {
-            current_player: self.current_player.other(),
-            piece_mask: flip_vertical(self.piece_mask),
-            player_one_mask: flip_vertical(self.piece_mask ^ self.player_one_mask),
-            bishop_mask: flip_vertical(self.bishop_mask),
-            king_mask: flip_vertical(self.king_mask),
-            knight_mask: flip_vertical(self.knight_mask),
-            rook_mask: flip_vertical(self.rook_mask),
-            pawn_mask: flip_vertical(self.pawn_mask),
+        let mut inv = self.flip();
+        inv.hash = inv.compute_hash();
+        inv
+    }
+
+    fn mirror_horizontal(&self) -> BitBoard {
+        let mut mirrored = BitBoard {
+            piece_mask: mirror_horizontal(self.piece_mask),
+            player_one_mask: mirror_horizontal(self.player_one_mask),
+            bishop_mask: mirror_horizontal(self.bishop_mask),
+            king_mask: mirror_horizontal(self.king_mask),
+            knight_mask: mirror_horizontal(self.knight_mask),
+            rook_mask: mirror_horizontal(self.rook_mask),
+            pawn_mask: mirror_horizontal(self.pawn_mask),
+            ..*self
         };
+        mirrored.hash = mirrored.compute_hash();
+        mirrored
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::simple::SimpleBoard;
+    use crate::test_support::{sq, KING_BY_BISHOP};
+    use rand::prelude::*;
 
     #[test]
     fn opening_board_moves() {
@@ -448,6 +951,145 @@ mod test {
         assert_eq!(moves, expected)
     }
 
+    #[test]
+    fn parse_moves() {
+        for mov in BitBoard::init().get_moves() {
+            assert_eq!(mov.to_string().parse::<BitBoardMove>(), Ok(mov));
+        }
+        assert_eq!(
+            "d2e3".parse::<BitBoardMove>(),
+            "D2E3".parse::<BitBoardMove>()
+        );
+        assert_eq!(
+            "A1G9".parse::<BitBoardMove>().unwrap().get_from_dest(),
+            (Square::new(0, 0).unwrap(), Square::new(8, 6).unwrap())
+        );
+        assert_eq!("A1A10".parse::<BitBoardMove>(), Err(MoveError::InvalidMove));
+        assert_eq!(
+            "A1Z1".parse::<BitBoardMove>(),
+            Err(MoveError::InvalidPosition)
+        );
+        assert_eq!(
+            "Bd2xf4".parse::<BitBoardMove>(),
+            "D2F4".parse::<BitBoardMove>()
+        );
+        assert_eq!(
+            "Bd3*".parse::<BitBoardMove>(),
+            "D3D3".parse::<BitBoardMove>()
+        );
+        assert_eq!(
+            "bb1-c3".parse::<BitBoardMove>(),
+            "B1C3".parse::<BitBoardMove>()
+        );
+        assert_eq!(
+            "Bd3*d3".parse::<BitBoardMove>(),
+            Err(MoveError::InvalidMove)
+        );
+        assert_eq!("Xd2e3".parse::<BitBoardMove>(), Err(MoveError::InvalidMove));
+    }
+
+    /// Whether `player` could capture on `square`, found by putting an enemy
+    /// pawn there if the square is empty and asking `SimpleBoard`.
+    fn attacked_by(board: &BitBoard, player: Player, square: Square) -> bool {
+        let mut position = Position::from_board(board);
+        let (row, col) = (square.row() as usize, square.col() as usize);
+        match position.rows[row][col] {
+            Some((owner, _)) if owner == player => return false,
+            Some(_) => {}
+            None => position.rows[row][col] = Some((player.other(), PieceKind::P)),
+        }
+        position.player = player;
+        SimpleBoard::from_position(&position)
+            .get_moves()
+            .iter()
+            .any(|mov| {
+                let (from, dest) = mov.get_from_dest();
+                from != dest && dest == square
+            })
+    }
+
+    #[test]
+    fn attack_maps() {
+        let mut rng = StdRng::seed_from_u64(13);
+
+        for _ in 0..100 {
+            let mut board = BitBoard::init();
+            while board.get_winner().is_none() {
+                for player in [Player::PlayerOne, Player::PlayerTwo] {
+                    let attacks = board.attacks(player);
+                    for square in Square::all() {
+                        assert_eq!(
+                            attacks & square.bit() != 0,
+                            attacked_by(&board, player, square),
+                            "{:?} attacks on {} in {}",
+                            player,
+                            square,
+                            board.to_position()
+                        );
+                    }
+
+                    let mut position = Position::from_board(&board);
+                    position.player = player.other();
+                    let mut expected = 0u64;
+                    for mov in SimpleBoard::from_position(&position).get_moves() {
+                        let (from, dest) = mov.get_from_dest();
+                        if from == dest {
+                            expected |= explosion_mask(from.index(), 1);
+                        }
+                    }
+                    let own = match player {
+                        Player::PlayerOne => board.piece_mask & board.player_one_mask,
+                        Player::PlayerTwo => board.piece_mask & !board.player_one_mask,
+                    };
+                    assert_eq!(board.explosion_threats(player), expected & own);
+                }
+
+                for square in Square::all() {
+                    let threats = board.threats_to(square);
+                    match board.get_piece(square) {
+                        Some((owner, _)) => {
+                            let attacked = attacked_by(&board, owner.other(), square);
+                            assert_eq!(threats != 0, attacked);
+                            assert_eq!(threats & !board.piece_mask, 0);
+                            assert_eq!(threats & !board.attackers(square, owner.other()), 0);
+                        }
+                        None => assert_eq!(threats, 0),
+                    }
+                }
+
+                let moves = board.get_moves();
+                board.do_move(moves.choose(&mut rng).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn attackers_of_square() {
+        let board = BitBoard::parse_position("3k3/7/7/3r3/2P1P2/2N4/3P3/7/3K3 2").unwrap();
+        // The rook on D6 is attacked by both pawns and the knight.
+        let attackers = board.threats_to(sq("D6"));
+        assert_eq!(attackers, sq("C5").bit() | sq("E5").bit() | sq("C4").bit());
+        // The rook captures backward, but not diagonally or through pieces.
+        assert_eq!(board.threats_to(sq("C5")), 0);
+        assert_eq!(board.threats_to(sq("D3")), sq("D6").bit());
+        assert_eq!(board.attackers(sq("D2"), Player::PlayerTwo), 0);
+        assert_eq!(board.threats_to(sq("D1")), 0);
+    }
+
+    #[test]
+    fn player_two_attacks() {
+        // PlayerTwo's king and pawn capture down the board.
+        let board = BitBoard::parse_position("3k3/7/7/3p3/7/7/7/7/3K3 2").unwrap();
+        let king = sq("C8").bit() | sq("D8").bit() | sq("E8").bit();
+        let pawn = sq("C5").bit() | sq("E5").bit();
+        assert_eq!(board.attacks(Player::PlayerTwo), king | pawn);
+        assert_eq!(board.attackers(sq("E5"), Player::PlayerTwo), sq("D6").bit());
+        assert_eq!(board.attackers(sq("D5"), Player::PlayerTwo), 0);
+        assert_eq!(board.attackers(sq("D7"), Player::PlayerTwo), 0);
+        let attacks = board.attacks(Player::PlayerOne);
+        assert_eq!(attacks, sq("C2").bit() | sq("D2").bit() | sq("E2").bit());
+    }
+
     #[test]
     fn test_flip_vertical() {
         assert_eq!(
@@ -455,4 +1097,143 @@ mod test {
             format!("{:x}", 0x7fffffffffffffffu64),
         );
     }
+
+    #[test]
+    fn incremental_scores() {
+        let mut rng = StdRng::seed_from_u64(21);
+        for _ in 0..200 {
+            let mut board = BitBoard::init();
+            while board.get_winner().is_none() {
+                assert_eq!(
+                    (board.material(), board.piece_square()),
+                    board.compute_scores()
+                );
+                let inverted = board.invert();
+                assert_eq!(
+                    (inverted.material(), inverted.piece_square()),
+                    inverted.compute_scores()
+                );
+                let mirrored = board.mirror_horizontal();
+                assert_eq!(
+                    (mirrored.material(), mirrored.piece_square()),
+                    mirrored.compute_scores()
+                );
+
+                let moves = board.get_moves();
+                let mov = *moves.choose(&mut rng).unwrap();
+                let before = (board.material(), board.piece_square());
+                let undo = board.make_move(&mov);
+                assert_eq!(
+                    (board.material(), board.piece_square()),
+                    board.compute_scores()
+                );
+                board.undo_move(&mov, &undo);
+                assert_eq!((board.material(), board.piece_square()), before);
+                board.do_move(&mov);
+            }
+        }
+        let start = BitBoard::init();
+        assert_eq!((start.material(), start.piece_square()), (0, 0));
+    }
+
+    #[test]
+    fn test_mirror_horizontal() {
+        let file_a = 0x0102040810204081u64;
+        assert_eq!(mirror_horizontal(file_a), file_a << 6);
+        assert_eq!(mirror_horizontal(0x7fffffffffffffff), 0x7fffffffffffffff);
+        // B1, C2 and G9 go to F1, E2 and A9.
+        assert_eq!(
+            mirror_horizontal((1 << 1) | (1 << 9) | (1 << 62)),
+            (1 << 5) | (1 << 11) | (1 << 56)
+        );
+        let mov: BitBoardMove = "B1C3".parse().unwrap();
+        assert_eq!(mov.mirror_horizontal().to_string(), "F1E3");
+        assert!(BitBoard::init().mirror_horizontal() == BitBoard::init());
+    }
+
+    #[test]
+    fn preview_explosion() {
+        // The mask-based override must agree with the default square scan.
+        let wide = RuleSet {
+            explosion_radius: 2,
+            ..RuleSet::default()
+        };
+        for rules in [RuleSet::default(), wide] {
+            let setup = Setup::new(KING_BY_BISHOP, rules).unwrap();
+            let simple = SimpleBoard::from_setup(&setup);
+            let bitboard = BitBoard::from_setup(&setup);
+            for square in Square::all() {
+                assert_eq!(
+                    bitboard.preview_explosion(square),
+                    simple.preview_explosion(square),
+                    "{square}"
+                );
+            }
+        }
+
+        let setup = Setup {
+            rules: wide,
+            ..Setup::default()
+        };
+        let preview = BitBoard::from_setup(&setup)
+            .preview_explosion(sq("D2"))
+            .unwrap();
+        assert_eq!(preview.squares.count_ones(), 20);
+        assert!(preview.catches_king(Player::PlayerOne));
+        // The back rank from B1 to F1, both bishops and the C4 and E4 pawns.
+        assert_eq!(
+            preview.material_swing(),
+            -(3 + 5 + 50 + 5 + 3 + 5 + 5 + 1 + 1)
+        );
+    }
+
+    #[test]
+    fn start_masks() {
+        // The hand-written start masks once had a pawn on D3 under the bishop.
+        let board = BitBoard::init();
+        let d3 = Square::new(2, 3).unwrap().bit();
+        assert_eq!(board.bishop_mask & board.pawn_mask, 0);
+        assert_eq!(board.pawn_mask & d3, 0);
+        assert_eq!(board.pawn_mask.count_ones(), 8);
+        assert_eq!(
+            board.get_piece(Square::new(2, 3).unwrap()),
+            Some((Player::PlayerOne, PieceKind::B))
+        );
+        assert_eq!(board.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate() {
+        let board = BitBoard::init();
+        assert_eq!(board.validate(), Ok(()));
+
+        let mut corrupt = board;
+        // Leave the D1 king with no kind.
+        corrupt.king_mask ^= 1 << 3;
+        assert_eq!(corrupt.validate(), Err(ValidationError::MissingKind(3)));
+        corrupt.bishop_mask |= 1 << 3;
+        assert_eq!(corrupt.validate(), Err(ValidationError::StaleHash));
+        corrupt.king_mask |= 1 << 3;
+        assert_eq!(corrupt.validate(), Err(ValidationError::KindOverlap(3)));
+
+        let mut corrupt = board;
+        corrupt.rook_mask |= 1 << 30;
+        assert_eq!(corrupt.validate(), Err(ValidationError::StrayKind(30)));
+        let mut corrupt = board;
+        corrupt.player_one_mask |= 1 << 30;
+        assert_eq!(corrupt.validate(), Err(ValidationError::OrphanedPlayer(30)));
+        let mut corrupt = board;
+        corrupt.pawn_mask |= 1 << 63;
+        assert_eq!(corrupt.validate(), Err(ValidationError::OffBoard));
+        let mut corrupt = board;
+        corrupt.knight_mask ^= 1 << 57;
+        corrupt.king_mask |= 1 << 57;
+        assert_eq!(
+            corrupt.validate(),
+            Err(ValidationError::TooManyKings(Player::PlayerTwo))
+        );
+        let mut corrupt = board;
+        corrupt.material += 1;
+        assert_eq!(corrupt.validate(), Err(ValidationError::StaleScore));
+    }
 }
@@ -2,20 +2,148 @@
 
 use std::fmt::Display;
 
+use crate::rules::RuleSet;
+pub use crate::square::Square;
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MoveError {
     NoSuchPiece,
     NotYourPiece,
     InvalidPosition,
     InvalidMove,
+    /// Piece-aware notation named the wrong piece or kind of move.
+    NotationMismatch,
 }
 
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoSuchPiece => write!(f, "there is no piece on that square"),
+            MoveError::NotYourPiece => write!(f, "that piece belongs to the other player"),
+            MoveError::InvalidPosition => write!(f, "that square is not on the board"),
+            MoveError::InvalidMove => write!(f, "that piece cannot move there"),
+            MoveError::NotationMismatch => {
+                write!(f, "that is not the piece or move on that square")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// A move as written, before it is checked against a board.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Notation {
+    pub piece: Option<PieceKind>,
+    pub from: Square,
+    pub dest: Square,
+    /// `Capture` for an `x` before the destination, `Explode` for a `*`.
+    pub kind: Option<MoveKind>,
+}
+
+/// Parses coordinate notation such as `D2E3`, or piece-aware notation such as
+/// `Nb1c3`, `Bd2xf4` and `Bd3*`.
+pub(crate) fn parse_notation(s: &str) -> Result<Notation, MoveError> {
+    let square = |file: u8, rank: u8| Square::from_chars(file, rank);
+    let bytes = s.trim().as_bytes();
+    // Two letters in a row can only be a piece followed by a file.
+    let (piece, bytes) = match bytes {
+        [piece, file, ..] if piece.is_ascii_alphabetic() && file.is_ascii_alphabetic() => {
+            let piece = PieceKind::from_letter(*piece as char).ok_or(MoveError::InvalidMove)?;
+            (Some(piece), &bytes[1..])
+        }
+        _ => (None, bytes),
+    };
+    let (from, rest) = match bytes {
+        [file, rank, rest @ ..] => (square(*file, *rank)?, rest),
+        _ => return Err(MoveError::InvalidMove),
+    };
+    let (dest, kind) = match rest {
+        [b'*'] => (from, Some(MoveKind::Explode)),
+        [b'x' | b'X', file, rank] => (square(*file, *rank)?, Some(MoveKind::Capture)),
+        [b'-', file, rank] | [file, rank] => (square(*file, *rank)?, None),
+        _ => return Err(MoveError::InvalidMove),
+    };
+    Ok(Notation {
+        piece,
+        from,
+        dest,
+        kind,
+    })
+}
+
+/// Parses a move in either notation into its from and dest squares.
+pub(crate) fn parse_from_dest(s: &str) -> Result<[Square; 2], MoveError> {
+    let notation = parse_notation(s)?;
+    Ok([notation.from, notation.dest])
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum PieceKind {
     B, K, N, R, P,
 }
 
+impl PieceKind {
+    pub const ALL: [PieceKind; 5] = [
+        PieceKind::B,
+        PieceKind::K,
+        PieceKind::N,
+        PieceKind::R,
+        PieceKind::P,
+    ];
 
+    pub fn letter(&self) -> char {
+        match self {
+            PieceKind::B => 'B',
+            PieceKind::K => 'K',
+            PieceKind::N => 'N',
+            PieceKind::R => 'R',
+            PieceKind::P => 'P',
+        }
+    }
+    /// The kind for a letter in either case.
+    pub fn from_letter(ch: char) -> Option<PieceKind> {
+        match ch.to_ascii_uppercase() {
+            'B' => Some(PieceKind::B),
+            'K' => Some(PieceKind::K),
+            'N' => Some(PieceKind::N),
+            'R' => Some(PieceKind::R),
+            'P' => Some(PieceKind::P),
+            _ => None,
+        }
+    }
+    /// Material value, as used by `SimpleBoard::eval`.
+    pub fn value(&self) -> i32 {
+        match self {
+            PieceKind::B => 5,
+            PieceKind::K => 50,
+            PieceKind::N => 3,
+            PieceKind::R => 5,
+            PieceKind::P => 1,
+        }
+    }
+    /// Piece-square bonus in tenths of a pawn for `player`'s piece on `square`.
+    pub fn square_value(&self, player: Player, square: Square) -> i32 {
+        let square = match player {
+            Player::PlayerOne => square,
+            Player::PlayerTwo => square.invert(),
+        };
+        crate::tables::PIECE_SQUARE[*self as usize][square.index() as usize]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            PieceKind::B => "bishop",
+            PieceKind::K => "king",
+            PieceKind::N => "knight",
+            PieceKind::R => "rook",
+            PieceKind::P => "pawn",
+        }
+    }
+}
+
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Player {
     PlayerOne,
@@ -43,16 +171,532 @@ impl Player {
     }
 }
 
-pub trait Mov: Display {
+/// How many pieces of each kind one player has.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PieceCounts([u8; 5]);
+
+impl PieceCounts {
+    pub fn get(&self, kind: PieceKind) -> u8 {
+        self.0[kind as usize]
+    }
+    pub(crate) fn add(&mut self, kind: PieceKind, count: u8) {
+        self.0[kind as usize] += count;
+    }
+    pub fn total(&self) -> u8 {
+        self.0.iter().sum()
+    }
+    /// Sum of `PieceKind::value` over the pieces.
+    pub fn value(&self) -> i32 {
+        PieceKind::ALL
+            .iter()
+            .map(|kind| kind.value() * self.get(*kind) as i32)
+            .sum()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MoveKind {
+    Quiet,
+    Capture,
+    /// A self-destruct, written with from equal to dest.
+    Explode,
+}
+
+/// What a move does, for showing to players.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MoveDescription {
+    pub player: Player,
+    pub piece: PieceKind,
+    pub from: Square,
+    pub dest: Square,
+    pub kind: MoveKind,
+    /// Pieces the move takes off the board, including an exploding piece.
+    pub removed: Vec<(Square, Player, PieceKind)>,
+}
+
+impl Display for MoveDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let piece =
+            |(square, player, kind): &(Square, Player, PieceKind)| match *player == self.player {
+                true => format!("{} {square}", kind.name()),
+                false => format!("enemy {} {square}", kind.name()),
+            };
+        let mover = format!("{} {}", self.piece.name(), self.from);
+        match self.kind {
+            MoveKind::Quiet => write!(f, "{mover} to {}", self.dest),
+            MoveKind::Capture => write!(f, "{mover} takes {}", piece(&self.removed[0])),
+            MoveKind::Explode => {
+                let removed: Vec<String> = self.removed.iter().map(piece).collect();
+                write!(f, "{mover} explodes, destroying {}", removed.join(", "))
+            }
+        }
+    }
+}
+
+/// The result of a self-destruct, worked out without playing it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExplosionPreview {
+    /// Every square in the blast, empty or not, as a mask of `Square::bit`s.
+    pub squares: u64,
+    /// Pieces the blast destroys, including the exploding piece.
+    pub destroyed: Vec<(Square, Player, PieceKind)>,
+}
+
+impl ExplosionPreview {
+    /// Change in material from PlayerOne's point of view: positive when
+    /// PlayerTwo loses more.
+    pub fn material_swing(&self) -> i32 {
+        self.destroyed
+            .iter()
+            .map(|(_, player, kind)| -(player.parity() as i32) * kind.value())
+            .sum()
+    }
+
+    pub fn catches_king(&self, player: Player) -> bool {
+        self.destroyed
+            .iter()
+            .any(|&(_, owner, kind)| owner == player && kind == PieceKind::K)
+    }
+}
+
+/// What a move took off the board, so that `Board::undo_move` can put it back:
+/// the captured piece, or everything in the blast of an explosion, including
+/// the exploding piece. Kept as masks of `Square::bit`s, so any blast radius
+/// fits in the same few words.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Undo {
+    /// Removed pieces of each kind, indexed in `PieceKind` order.
+    kinds: [u64; 5],
+    /// Which of the removed pieces belonged to PlayerOne.
+    player_one: u64,
+}
+
+impl Undo {
+    pub(crate) fn push(&mut self, pos: u8, player: Player, kind: PieceKind) {
+        self.kinds[kind as usize] |= 1 << pos;
+        if player == Player::PlayerOne {
+            self.player_one |= 1 << pos;
+        }
+    }
+
+    /// Removed pieces by square index, in square order.
+    pub fn removed(&self) -> impl Iterator<Item = (u8, Player, PieceKind)> + '_ {
+        let mut remaining = self.kinds.iter().fold(0, |all, mask| all | mask);
+        std::iter::from_fn(move || {
+            let pos = remaining.trailing_zeros() as u8;
+            remaining &= remaining.checked_sub(1)?;
+            let kind = *PieceKind::ALL
+                .iter()
+                .find(|kind| self.kinds[**kind as usize] & (1 << pos) != 0)?;
+            let player = match self.player_one & (1 << pos) {
+                0 => Player::PlayerTwo,
+                _ => Player::PlayerOne,
+            };
+            Some((pos, player, kind))
+        })
+    }
+}
+
+// Games from the standard start never gain pieces, and their eleven pieces a
+// side generate well under a hundred moves. Arbitrary parsed positions can do
+// more; searches over them have topped out around 200, so 256 leaves headroom.
+const MAX_MOVES: usize = 256;
+
+/// Moves generated without allocating, for `Board::get_moves_into`.
+#[derive(Clone, Copy)]
+pub struct MoveList<M: Mov> {
+    moves: [M; MAX_MOVES],
+    len: usize,
+}
+
+impl<M: Mov> MoveList<M> {
+    /// The most moves a list can hold; see `MAX_MOVES` for why it is enough.
+    pub const CAPACITY: usize = MAX_MOVES;
+
+    pub fn new() -> Self {
+        MoveList {
+            moves: [M::default(); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, mov: M) {
+        debug_assert!(self.len < MAX_MOVES, "more than {MAX_MOVES} moves");
+        self.moves[self.len] = mov;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<M: Mov> Default for MoveList<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Mov> std::ops::Deref for MoveList<M> {
+    type Target = [M];
+
+    fn deref(&self) -> &[M] {
+        &self.moves[..self.len]
+    }
+}
+
+impl<M: Mov> std::ops::DerefMut for MoveList<M> {
+    fn deref_mut(&mut self) -> &mut [M] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl<'a, M: Mov> IntoIterator for &'a MoveList<M> {
+    type Item = &'a M;
+    type IntoIter = std::slice::Iter<'a, M>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub trait Mov: Display + Copy + Default {
     fn invert(&self) -> Self;
-    fn get_from_dest(&self) -> ((u8, u8), (u8, u8));
+    /// The same move reflected across the D file.
+    fn mirror_horizontal(&self) -> Self;
+    fn get_from_dest(&self) -> (Square, Square);
+}
+
+/// Sums `score` over every piece on `board`, for recounting from scratch.
+fn sum_pieces<M: Mov, B: Board<M> + ?Sized>(
+    board: &B,
+    score: impl Fn(Player, PieceKind, Square) -> i32,
+) -> i32 {
+    board
+        .pieces()
+        .map(|(square, player, kind)| score(player, kind, square))
+        .sum()
 }
 
 pub trait Board<M: Mov> {
-    fn get_piece(&self, row: u8, col: u8) -> Option<(Player, PieceKind)>;
+    fn get_piece(&self, square: Square) -> Option<(Player, PieceKind)>;
+    /// Every piece on the board with its square, in square order.
+    fn pieces(&self) -> impl Iterator<Item = (Square, Player, PieceKind)> + '_ {
+        Square::all().filter_map(|square| {
+            self.get_piece(square)
+                .map(|(player, kind)| (square, player, kind))
+        })
+    }
+    fn piece_counts(&self, player: Player) -> PieceCounts {
+        let mut counts = PieceCounts::default();
+        for (_, owner, kind) in self.pieces() {
+            if owner == player {
+                counts.add(kind, 1);
+            }
+        }
+        counts
+    }
+    /// Where `player`'s king is, or `None` once it has been blown up.
+    fn king_square(&self, player: Player) -> Option<Square> {
+        self.pieces()
+            .find(|&(_, owner, kind)| owner == player && kind == PieceKind::K)
+            .map(|(square, _, _)| square)
+    }
     fn get_player(&self) -> Player;
+    fn get_rules(&self) -> RuleSet;
+    /// Zobrist key of the position, maintained incrementally by `do_move`.
+    fn get_hash(&self) -> u64;
     fn get_moves(&self) -> Vec<M>;
+    /// Appends the moves `get_moves` would return to `moves`.
+    fn get_moves_into(&self, moves: &mut MoveList<M>);
+    /// The moves `get_moves` would return for the piece on `square`, which is
+    /// none unless it belongs to the side to move.
+    fn get_moves_from(&self, square: Square) -> Vec<M> {
+        let mut moves = self.get_moves();
+        moves.retain(|mov| mov.get_from_dest().0 == square);
+        moves
+    }
+    /// Whether the side to move has a move, without generating them all.
+    fn has_any_move(&self) -> bool {
+        !self.get_moves().is_empty()
+    }
     fn get_winner(&self) -> Option<Player>;
+    /// Material balance from PlayerOne's point of view, using `PieceKind::value`.
+    fn material(&self) -> i32 {
+        sum_pieces(self, |player, kind, _| {
+            kind.value() * player.parity() as i32
+        })
+    }
+    /// Sum of `PieceKind::square_value` from PlayerOne's point of view, in
+    /// tenths of a pawn.
+    fn piece_square(&self) -> i32 {
+        sum_pieces(self, |player, kind, square| {
+            kind.square_value(player, square) * player.parity() as i32
+        })
+    }
+    fn move_kind(&self, mov: &M) -> MoveKind {
+        let (from, dest) = mov.get_from_dest();
+        match self.get_piece(dest) {
+            _ if from == dest => MoveKind::Explode,
+            Some(_) => MoveKind::Capture,
+            None => MoveKind::Quiet,
+        }
+    }
+    /// What a self-destruct of the piece on `square` would destroy, or `None`
+    /// if the square is empty. Legality is not checked.
+    fn preview_explosion(&self, square: Square) -> Option<ExplosionPreview> {
+        self.get_piece(square)?;
+        let mut preview = ExplosionPreview {
+            squares: 0,
+            destroyed: vec![],
+        };
+        for target in Square::all() {
+            if !self.get_rules().in_blast(square, target) {
+                continue;
+            }
+            preview.squares |= target.bit();
+            if let Some((player, kind)) = self.get_piece(target) {
+                preview.destroyed.push((target, player, kind));
+            }
+        }
+        Some(preview)
+    }
+    /// What `mov` would do, or `None` if there is no piece to move.
+    fn describe_move(&self, mov: &M) -> Option<MoveDescription> {
+        let (from, dest) = mov.get_from_dest();
+        let (player, piece) = self.get_piece(from)?;
+        let kind = self.move_kind(mov);
+        let removed = match kind {
+            MoveKind::Quiet => vec![],
+            MoveKind::Capture => {
+                let (owner, captured) = self.get_piece(dest)?;
+                vec![(dest, owner, captured)]
+            }
+            MoveKind::Explode => self.preview_explosion(from)?.destroyed,
+        };
+        Some(MoveDescription {
+            player,
+            piece,
+            from,
+            dest,
+            kind,
+            removed,
+        })
+    }
+    /// `mov` in piece-aware notation, such as `Nb1c3`, `Bd2xf4` or `Bd3*`.
+    fn move_notation(&self, mov: &M) -> String {
+        let (from, dest) = mov.get_from_dest();
+        let piece = match self.get_piece(from) {
+            Some((_, kind)) => kind.letter(),
+            None => return mov.to_string(),
+        };
+        let from_name = from.to_string().to_lowercase();
+        let dest_name = dest.to_string().to_lowercase();
+        match self.move_kind(mov) {
+            MoveKind::Quiet => format!("{piece}{from_name}{dest_name}"),
+            MoveKind::Capture => format!("{piece}{from_name}x{dest_name}"),
+            MoveKind::Explode => format!("{piece}{from_name}*"),
+        }
+    }
+    /// Parses and checks a move in either notation. A piece letter, `x` or
+    /// `*` must agree with the board; leaving them out is never an error.
+    fn parse_move(&self, s: &str) -> Result<M, MoveError>
+    where
+        M: std::str::FromStr<Err = MoveError>,
+    {
+        let notation = parse_notation(s)?;
+        let mov: M = s.parse()?;
+        self.check_move(&mov)?;
+        let piece = self.get_piece(notation.from).map(|(_, kind)| kind);
+        if notation.piece.is_some_and(|named| Some(named) != piece)
+            || notation
+                .kind
+                .is_some_and(|kind| kind != self.move_kind(&mov))
+        {
+            return Err(MoveError::NotationMismatch);
+        }
+        Ok(mov)
+    }
+    /// Why `mov` cannot be played in this position, if it cannot.
+    fn check_move(&self, mov: &M) -> Result<(), MoveError> {
+        let (from, dest) = mov.get_from_dest();
+        match self.get_piece(from) {
+            None => return Err(MoveError::NoSuchPiece),
+            Some((player, _)) if player != self.get_player() => {
+                return Err(MoveError::NotYourPiece)
+            }
+            _ => {}
+        }
+        match self
+            .get_moves()
+            .iter()
+            .any(|legal| legal.get_from_dest() == (from, dest))
+        {
+            true => Ok(()),
+            false => Err(MoveError::InvalidMove),
+        }
+    }
+    /// Unchecked: an illegal `mov` may panic or leave the board inconsistent.
     fn do_move(&mut self, mov: &M);
+    /// `do_move` for untrusted input; the board is left untouched on error.
+    fn try_do_move(&mut self, mov: &M) -> Result<(), MoveError> {
+        self.check_move(mov)?;
+        self.do_move(mov);
+        Ok(())
+    }
+    /// `do_move`, also returning what is needed to `undo_move` it.
+    fn make_move(&mut self, mov: &M) -> Undo;
+    /// Takes back `mov`, which must be the last move made with `make_move`.
+    fn undo_move(&mut self, mov: &M, undo: &Undo);
     fn invert(&self) -> Self;
+    /// The board reflected across the D file, with the same side to move.
+    fn mirror_horizontal(&self) -> Self;
+
+    /// One representative of the board and its mirror image, and whether it is
+    /// the mirror. Moves chosen on the representative map back to this board
+    /// through `Mov::mirror_horizontal` when the flag is set.
+    fn canonical(&self) -> (Self, bool)
+    where
+        Self: Sized + Clone,
+    {
+        let mirrored = self.mirror_horizontal();
+        match mirrored.get_hash() < self.get_hash() {
+            true => (mirrored, true),
+            false => (self.clone(), false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simple::SimpleBoard;
+    use crate::test_support::{sq, KING_BY_BISHOP};
+
+    #[test]
+    fn move_notation() {
+        let board = SimpleBoard::parse_position(KING_BY_BISHOP).unwrap();
+        let notation = |mov: &str| board.move_notation(&board.parse_move(mov).unwrap());
+        assert_eq!(notation("B9C7"), "Nb9c7");
+        assert_eq!(notation("C4C3"), "Rc4xc3");
+        assert_eq!(notation("E8E8"), "Ke8*");
+        for mov in board.get_moves() {
+            assert_eq!(board.parse_move(&board.move_notation(&mov)), Ok(mov));
+        }
+
+        assert_eq!(
+            board.parse_move("nb9-c7").unwrap().get_from_dest(),
+            (sq("B9"), sq("C7"))
+        );
+        assert_eq!(
+            board.parse_move("C4xC3").unwrap().get_from_dest(),
+            (sq("C4"), sq("C3"))
+        );
+        assert_eq!(
+            board.parse_move("e8*").unwrap().get_from_dest(),
+            (sq("E8"), sq("E8"))
+        );
+        assert_eq!(board.parse_move("Bb9c7"), Err(MoveError::NotationMismatch));
+        assert_eq!(board.parse_move("Nb9xc7"), Err(MoveError::NotationMismatch));
+        assert!(board.parse_move("Rc4c3").is_ok());
+        assert_eq!(board.parse_move("Qb9c7"), Err(MoveError::InvalidMove));
+        assert_eq!(board.parse_move("Rc3*"), Err(MoveError::NotYourPiece));
+
+        let explosion = board
+            .describe_move(&board.parse_move("Ke8*").unwrap())
+            .unwrap();
+        assert_eq!(explosion.kind, MoveKind::Explode);
+        assert_eq!(explosion.piece, PieceKind::K);
+        assert_eq!(explosion.removed.len(), 4);
+        assert_eq!(
+            explosion.to_string(),
+            "king E8 explodes, destroying enemy bishop D8, king E8, rook E9, knight F9"
+        );
+        let capture = board
+            .describe_move(&board.parse_move("C4E4").unwrap())
+            .unwrap();
+        assert_eq!(
+            capture.removed,
+            vec![(sq("E4"), Player::PlayerOne, PieceKind::P)]
+        );
+        assert_eq!(capture.to_string(), "rook C4 takes enemy pawn E4");
+
+        let start = SimpleBoard::init();
+        assert_eq!(start.move_kind(&"D2E3".parse().unwrap()), MoveKind::Quiet);
+        assert_eq!(
+            start
+                .describe_move(&"D2E3".parse().unwrap())
+                .unwrap()
+                .to_string(),
+            "bishop D2 to E3"
+        );
+    }
+
+    #[test]
+    fn preview_explosion() {
+        let board = SimpleBoard::parse_position(KING_BY_BISHOP).unwrap();
+        let preview = board.preview_explosion(sq("E8")).unwrap();
+        assert_eq!(preview.squares.count_ones(), 9);
+        assert_eq!(preview.squares & sq("F9").bit(), sq("F9").bit());
+        assert_eq!(
+            preview.destroyed,
+            vec![
+                (sq("D8"), Player::PlayerOne, PieceKind::B),
+                (sq("E8"), Player::PlayerTwo, PieceKind::K),
+                (sq("E9"), Player::PlayerTwo, PieceKind::R),
+                (sq("F9"), Player::PlayerTwo, PieceKind::N),
+            ]
+        );
+        assert_eq!(preview.material_swing(), 58 - 5);
+        assert!(preview.catches_king(Player::PlayerTwo));
+        assert!(!preview.catches_king(Player::PlayerOne));
+        // Previewing leaves the board alone, and empty squares have nothing to explode.
+        assert!(board.get_piece(sq("E8")).is_some());
+        assert_eq!(board.preview_explosion(sq("D5")), None);
+    }
+
+    #[test]
+    fn pieces() {
+        let mut board = SimpleBoard::parse_position(KING_BY_BISHOP).unwrap();
+        let pieces: Vec<_> = board.pieces().collect();
+        assert_eq!(pieces.len(), 13);
+        assert_eq!(pieces[0], (sq("D2"), Player::PlayerOne, PieceKind::K));
+        assert_eq!(
+            pieces.last(),
+            Some(&(sq("F9"), Player::PlayerTwo, PieceKind::N))
+        );
+
+        let ones = board.piece_counts(Player::PlayerOne);
+        let twos = board.piece_counts(Player::PlayerTwo);
+        assert_eq!(
+            [
+                ones.get(PieceKind::B),
+                ones.get(PieceKind::R),
+                ones.get(PieceKind::P)
+            ],
+            [2, 2, 2]
+        );
+        assert_eq!([twos.get(PieceKind::N), twos.get(PieceKind::P)], [2, 0]);
+        assert_eq!((ones.total(), twos.total()), (8, 5));
+        assert_eq!(ones.value() - twos.value(), board.material());
+
+        assert_eq!(board.king_square(Player::PlayerOne), Some(sq("D2")));
+        assert_eq!(board.king_square(Player::PlayerTwo), Some(sq("E8")));
+        board.do_move(&"E8E8".parse().unwrap());
+        assert_eq!(board.king_square(Player::PlayerTwo), None);
+        assert_eq!(board.piece_counts(Player::PlayerTwo).get(PieceKind::K), 0);
+    }
+
+    #[test]
+    fn canonical() {
+        let board = SimpleBoard::parse_position(KING_BY_BISHOP).unwrap();
+        let mirrored = board.mirror_horizontal();
+        assert_eq!(
+            mirrored.to_position(),
+            "1nr2n1/2kB3/7/7/7/P1P1r2/N2BR2/2RK3/7 2"
+        );
+        // The board is its own representative, and its mirror maps onto it.
+        assert!(board.canonical() == (board, false));
+        assert!(mirrored.canonical() == (board, true));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,435 @@
+//! A conformance harness for `Board` implementations.
+//!
+//! `check_board` plays seeded random games from a starting board and compares
+//! the board under test against `SimpleBoard` after every move. A new backend
+//! can be checked with a single call:
+//!
+//! ```
+//! use newcular::{bitboard::BitBoard, conformance::check_board};
+//!
+//! check_board(&BitBoard::init(), 10, 42).unwrap();
+//! ```
+//!
+//! Checking `SimpleBoard` this way compares it with itself and proves nothing.
+//! `check_board_against` takes the reference as a parameter, so `SimpleBoard`,
+//! or any backend, can be compared with `BitBoard` instead.
+
+use std::fmt::Display;
+
+use crate::board::*;
+use crate::position::Position;
+use crate::rules::Setup;
+use crate::simple::SimpleBoard;
+use crate::zobrist::{hash_grid, splitmix64};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Check {
+    /// `get_moves` differs from the reference.
+    MoveGeneration,
+    /// A generated move fails `check_move` or moves a piece that is not the mover's.
+    Legality,
+    Winner,
+    /// `get_hash` differs from the Zobrist hash of the position.
+    Hash,
+    /// `material` or `piece_square` differs from the reference.
+    Score,
+    /// The position after a move differs from the reference, including as
+    /// reported by `pieces`, `piece_counts` or `king_square`.
+    Placement,
+    /// A self-destruct left a piece in its blast area, or `preview_explosion`
+    /// differs from the reference.
+    Explosion,
+    /// `invert` differs from the reference, or inverting twice changes the board.
+    Invert,
+    /// `mirror_horizontal` differs from the reference, or its moves are not the
+    /// mirrored moves of the board.
+    Mirror,
+    /// `undo_move` did not restore the position.
+    Undo,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConformanceFailure {
+    pub check: Check,
+    /// Index of the random game that failed.
+    pub game: usize,
+    /// The moves played from the starting board to reach `position`.
+    pub moves: Vec<String>,
+    pub position: String,
+    pub detail: String,
+}
+
+impl Display for ConformanceFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} check failed in game {} at {} after [{}]: {}",
+            self.check,
+            self.game,
+            self.position,
+            self.moves.join(" "),
+            self.detail
+        )
+    }
+}
+
+impl std::error::Error for ConformanceFailure {}
+
+/// Plays `games` random games from `start`, seeded by `seed`, checking the
+/// board against the `SimpleBoard` reference after every move.
+pub fn check_board<M: Mov, B: Board<M> + Clone>(
+    start: &B,
+    games: usize,
+    seed: u64,
+) -> Result<(), ConformanceFailure> {
+    let reference = SimpleBoard::from_setup(&Setup {
+        position: Position::from_board(start),
+        rules: start.get_rules(),
+    });
+    check_board_against(start, &reference, games, seed)
+}
+
+/// `check_board` with `reference_start`, which must hold the same position and
+/// rules as `start`, as the implementation to trust.
+pub fn check_board_against<M, B, RM, R>(
+    start: &B,
+    reference_start: &R,
+    games: usize,
+    seed: u64,
+) -> Result<(), ConformanceFailure>
+where
+    M: Mov,
+    B: Board<M> + Clone,
+    RM: Mov,
+    R: Board<RM> + Clone,
+{
+    let mut state = seed;
+    for game in 0..games {
+        let mut board = start.clone();
+        let mut reference = reference_start.clone();
+        let mut moves = vec![];
+        loop {
+            check_position(&board, &reference)
+                .map_err(|(check, detail)| failure(check, game, &moves, &reference, detail))?;
+            if reference.get_winner().is_some() {
+                break;
+            }
+
+            let mut board_moves = board.get_moves();
+            board_moves.sort_by_key(|mov| mov.get_from_dest());
+            let (next_state, random) = splitmix64(state);
+            state = next_state;
+            let mov = &board_moves[(random % board_moves.len() as u64) as usize];
+            let reference_move = reference
+                .get_moves()
+                .into_iter()
+                .find(|legal| legal.get_from_dest() == mov.get_from_dest())
+                .unwrap();
+
+            check_undo(&board)
+                .map_err(|detail| failure(Check::Undo, game, &moves, &reference, detail))?;
+            moves.push(mov.to_string());
+            board.do_move(mov);
+            reference.do_move(&reference_move);
+            check_explosion(&board, mov)
+                .map_err(|detail| failure(Check::Explosion, game, &moves, &reference, detail))?;
+        }
+    }
+    Ok(())
+}
+
+fn failure<RM: Mov, R: Board<RM>>(
+    check: Check,
+    game: usize,
+    moves: &[String],
+    reference: &R,
+    detail: String,
+) -> ConformanceFailure {
+    ConformanceFailure {
+        check,
+        game,
+        moves: moves.to_vec(),
+        position: Position::from_board(reference).to_string(),
+        detail,
+    }
+}
+
+fn sorted_from_dest<M: Mov, B: Board<M>>(board: &B) -> Vec<(Square, Square)> {
+    let mut moves: Vec<_> = board.get_moves().iter().map(Mov::get_from_dest).collect();
+    moves.sort();
+    moves
+}
+
+fn check_position<M, B, RM, R>(board: &B, reference: &R) -> Result<(), (Check, String)>
+where
+    M: Mov,
+    B: Board<M> + Clone,
+    RM: Mov,
+    R: Board<RM>,
+{
+    let position = Position::from_board(board);
+    let expected = Position::from_board(reference);
+    if position != expected {
+        return Err((Check::Placement, format!("board is {position}")));
+    }
+    if !board.pieces().eq(reference.pieces()) {
+        return Err((
+            Check::Placement,
+            "pieces() differs from the reference".to_string(),
+        ));
+    }
+    for player in [Player::PlayerOne, Player::PlayerTwo] {
+        if board.piece_counts(player) != reference.piece_counts(player) {
+            return Err((
+                Check::Placement,
+                format!("{player:?} has {:?}", board.piece_counts(player)),
+            ));
+        }
+        if board.king_square(player) != reference.king_square(player) {
+            return Err((
+                Check::Placement,
+                format!("{player:?} king is on {:?}", board.king_square(player)),
+            ));
+        }
+    }
+    if board.get_hash() != hash_grid(&position.rows, position.player) {
+        return Err((Check::Hash, format!("hash is {:#018x}", board.get_hash())));
+    }
+    let (score, expected) = (
+        (board.material(), board.piece_square()),
+        (reference.material(), reference.piece_square()),
+    );
+    if score != expected {
+        return Err((
+            Check::Score,
+            format!("scores are {score:?}, expected {expected:?}"),
+        ));
+    }
+    if board.get_winner() != reference.get_winner() {
+        return Err((
+            Check::Winner,
+            format!(
+                "winner is {:?}, expected {:?}",
+                board.get_winner(),
+                reference.get_winner()
+            ),
+        ));
+    }
+    if reference.get_winner().is_some() {
+        return Ok(());
+    }
+
+    for mov in board.get_moves() {
+        let (from, _) = mov.get_from_dest();
+        let owner = board.get_piece(from).map(|(player, _)| player);
+        if owner != Some(board.get_player()) {
+            return Err((
+                Check::Legality,
+                format!("{mov} does not move a piece of the side to move"),
+            ));
+        }
+        if let Err(err) = board.check_move(&mov) {
+            return Err((Check::Legality, format!("{mov} was generated but {err}")));
+        }
+    }
+    if sorted_from_dest(board) != sorted_from_dest(reference) {
+        let moves: Vec<String> = board
+            .get_moves()
+            .iter()
+            .map(|mov| mov.to_string())
+            .collect();
+        return Err((
+            Check::MoveGeneration,
+            format!("moves are {}", moves.join(" ")),
+        ));
+    }
+    let mut move_list = MoveList::new();
+    board.get_moves_into(&mut move_list);
+    let listed: Vec<_> = move_list.iter().map(Mov::get_from_dest).collect();
+    let generated: Vec<_> = board.get_moves().iter().map(Mov::get_from_dest).collect();
+    if listed != generated {
+        return Err((
+            Check::MoveGeneration,
+            "get_moves_into differs from get_moves".to_string(),
+        ));
+    }
+    let reference_moves = sorted_from_dest(reference);
+    for square in Square::all() {
+        let mut from: Vec<_> = board
+            .get_moves_from(square)
+            .iter()
+            .map(Mov::get_from_dest)
+            .collect();
+        from.sort();
+        let expected: Vec<_> = reference_moves
+            .iter()
+            .copied()
+            .filter(|(from, _)| *from == square)
+            .collect();
+        if from != expected {
+            return Err((
+                Check::MoveGeneration,
+                format!("get_moves_from({square}) differs"),
+            ));
+        }
+    }
+    if !board.has_any_move() {
+        return Err((
+            Check::MoveGeneration,
+            "has_any_move is false with moves left".to_string(),
+        ));
+    }
+    for mov in board.get_moves() {
+        let (from, dest) = mov.get_from_dest();
+        if from == dest && board.preview_explosion(from) != reference.preview_explosion(from) {
+            return Err((
+                Check::Explosion,
+                format!("preview of {mov} differs from the reference"),
+            ));
+        }
+    }
+
+    let inverted = board.invert();
+    if Position::from_board(&inverted) != Position::from_board(&reference.invert()) {
+        return Err((
+            Check::Invert,
+            format!("inverted board is {}", Position::from_board(&inverted)),
+        ));
+    }
+    if sorted_from_dest(&inverted) != sorted_from_dest(&reference.invert()) {
+        return Err((
+            Check::Invert,
+            "inverted board generates different moves".to_string(),
+        ));
+    }
+    let restored = inverted.invert();
+    if Position::from_board(&restored) != position || restored.get_hash() != board.get_hash() {
+        return Err((
+            Check::Invert,
+            "inverting twice changed the board".to_string(),
+        ));
+    }
+
+    let mirrored = board.mirror_horizontal();
+    if Position::from_board(&mirrored) != Position::from_board(&reference.mirror_horizontal()) {
+        return Err((
+            Check::Mirror,
+            format!("mirrored board is {}", Position::from_board(&mirrored)),
+        ));
+    }
+    let mut mirrored_moves: Vec<_> = board
+        .get_moves()
+        .iter()
+        .map(|mov| mov.mirror_horizontal().get_from_dest())
+        .collect();
+    mirrored_moves.sort();
+    if sorted_from_dest(&mirrored) != mirrored_moves {
+        return Err((
+            Check::Mirror,
+            "mirrored board generates different moves".to_string(),
+        ));
+    }
+    let (canonical, _) = board.canonical();
+    if canonical.get_hash() != mirrored.canonical().0.get_hash() {
+        return Err((
+            Check::Mirror,
+            "board and mirror have different canonical forms".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_undo<M: Mov, B: Board<M> + Clone>(board: &B) -> Result<(), String> {
+    let position = Position::from_board(board);
+    let mut made = board.clone();
+    for mov in board.get_moves() {
+        let undo = made.make_move(&mov);
+        made.undo_move(&mov, &undo);
+        if Position::from_board(&made) != position || made.get_hash() != board.get_hash() {
+            return Err(format!(
+                "undoing {mov} left {}",
+                Position::from_board(&made)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_explosion<M: Mov, B: Board<M>>(board: &B, mov: &M) -> Result<(), String> {
+    let (from, dest) = mov.get_from_dest();
+    if from != dest {
+        return Ok(());
+    }
+    for square in Square::all() {
+        if !board.get_rules().in_blast(from, square) {
+            continue;
+        }
+        if let Some((player, kind)) = board.get_piece(square) {
+            return Err(format!("{mov} left {player:?}'s {kind:?} on {square}"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::RuleSet;
+    use crate::simple::SimpleMove;
+
+    /// A backend that forgets self-destructs.
+    #[derive(Clone)]
+    struct NoExplosions(SimpleBoard);
+
+    impl Board<SimpleMove> for NoExplosions {
+        fn get_piece(&self, square: Square) -> Option<(Player, PieceKind)> {
+            self.0.get_piece(square)
+        }
+        fn get_player(&self) -> Player {
+            self.0.get_player()
+        }
+        fn get_rules(&self) -> RuleSet {
+            self.0.get_rules()
+        }
+        fn get_hash(&self) -> u64 {
+            self.0.get_hash()
+        }
+        fn get_moves(&self) -> Vec<SimpleMove> {
+            let mut moves = self.0.get_moves();
+            moves.retain(|mov| mov.get_from_dest().0 != mov.get_from_dest().1);
+            moves
+        }
+        fn get_moves_into(&self, moves: &mut MoveList<SimpleMove>) {
+            for mov in self.get_moves() {
+                moves.push(mov);
+            }
+        }
+        fn get_winner(&self) -> Option<Player> {
+            self.0.get_winner()
+        }
+        fn do_move(&mut self, mov: &SimpleMove) {
+            self.0.do_move(mov)
+        }
+        fn make_move(&mut self, mov: &SimpleMove) -> Undo {
+            self.0.make_move(mov)
+        }
+        fn undo_move(&mut self, mov: &SimpleMove, undo: &Undo) {
+            self.0.undo_move(mov, undo)
+        }
+        fn invert(&self) -> Self {
+            NoExplosions(self.0.invert())
+        }
+        fn mirror_horizontal(&self) -> Self {
+            NoExplosions(self.0.mirror_horizontal())
+        }
+    }
+
+    #[test]
+    fn reports_first_failure() {
+        let failure = check_board(&NoExplosions(SimpleBoard::init()), 1, 0).unwrap_err();
+        assert_eq!(failure.check, Check::MoveGeneration);
+        assert_eq!(failure.game, 0);
+        assert!(failure.moves.is_empty());
+        assert_eq!(failure.position, crate::position::START_POSITION);
+    }
+}
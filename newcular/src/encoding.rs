@@ -0,0 +1,318 @@
+//! A fixed-size binary encoding of positions, and a stream format for
+//! datasets of positions labelled with the game result and a search score.
+//!
+//! An encoded position is the seven `BitBoard` masks as little-endian `u64`s,
+//! in the order piece, player one, bishop, king, knight, rook, pawn. The board
+//! only has 63 squares, so the top bit of the piece mask holds the side to
+//! move (set for player two). Rules are not encoded.
+
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+use crate::bitboard::{BitBoard, ValidationError};
+use crate::board::*;
+use crate::position::Position;
+use crate::record::GameResult;
+
+pub const ENCODED_LEN: usize = 56;
+
+/// Written at the start of every position stream, including a format version.
+pub const STREAM_MAGIC: [u8; 8] = *b"NCPOS\0\0\x01";
+
+/// An encoded position followed by the result byte and the score.
+pub const LABELLED_LEN: usize = ENCODED_LEN + 1 + 4;
+
+const SIDE_TO_MOVE: u64 = 1 << 63;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The masks do not describe a valid board.
+    Invalid(ValidationError),
+    InvalidResult(u8),
+    /// A stream did not start with `STREAM_MAGIC`.
+    BadMagic,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Invalid(err) => write!(f, "invalid position: {err}"),
+            DecodeError::InvalidResult(byte) => write!(f, "invalid result byte {byte}"),
+            DecodeError::BadMagic => write!(f, "not a position stream"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for io::Error {
+    fn from(err: DecodeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+impl BitBoard {
+    pub fn to_bytes(&self) -> [u8; ENCODED_LEN] {
+        let side = match self.get_player() {
+            Player::PlayerOne => 0,
+            Player::PlayerTwo => SIDE_TO_MOVE,
+        };
+        let masks = [
+            self.piece_mask | side,
+            self.player_one_mask,
+            self.bishop_mask,
+            self.king_mask,
+            self.knight_mask,
+            self.rook_mask,
+            self.pawn_mask,
+        ];
+        let mut bytes = [0; ENCODED_LEN];
+        for (chunk, mask) in bytes.chunks_exact_mut(8).zip(masks) {
+            chunk.copy_from_slice(&mask.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a position under the default rules.
+    pub fn from_bytes(bytes: &[u8; ENCODED_LEN]) -> Result<Self, DecodeError> {
+        let mut masks = [0u64; 7];
+        for (mask, chunk) in masks.iter_mut().zip(bytes.chunks_exact(8)) {
+            *mask = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let player = match masks[0] & SIDE_TO_MOVE {
+            0 => Player::PlayerOne,
+            _ => Player::PlayerTwo,
+        };
+        masks[0] &= !SIDE_TO_MOVE;
+        BitBoard::from_masks(player, masks).map_err(DecodeError::Invalid)
+    }
+}
+
+impl Position {
+    pub fn to_bytes(&self) -> [u8; ENCODED_LEN] {
+        BitBoard::from_position(self).to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8; ENCODED_LEN]) -> Result<Self, DecodeError> {
+        Ok(Position::from_board(&BitBoard::from_bytes(bytes)?))
+    }
+}
+
+/// A dataset entry: a position, how its game ended, and a search score from
+/// player one's point of view.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LabelledPosition {
+    pub position: Position,
+    pub result: GameResult,
+    pub score: i32,
+}
+
+fn result_byte(result: GameResult) -> u8 {
+    match result {
+        GameResult::Unfinished => 0,
+        GameResult::Win(Player::PlayerOne) => 1,
+        GameResult::Win(Player::PlayerTwo) => 2,
+        GameResult::Draw => 3,
+    }
+}
+
+fn result_from_byte(byte: u8) -> Result<GameResult, DecodeError> {
+    match byte {
+        0 => Ok(GameResult::Unfinished),
+        1 => Ok(GameResult::Win(Player::PlayerOne)),
+        2 => Ok(GameResult::Win(Player::PlayerTwo)),
+        3 => Ok(GameResult::Draw),
+        _ => Err(DecodeError::InvalidResult(byte)),
+    }
+}
+
+impl LabelledPosition {
+    pub fn to_bytes(&self) -> [u8; LABELLED_LEN] {
+        let mut bytes = [0; LABELLED_LEN];
+        bytes[..ENCODED_LEN].copy_from_slice(&self.position.to_bytes());
+        bytes[ENCODED_LEN] = result_byte(self.result);
+        bytes[ENCODED_LEN + 1..].copy_from_slice(&self.score.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; LABELLED_LEN]) -> Result<Self, DecodeError> {
+        Ok(LabelledPosition {
+            position: Position::from_bytes(bytes[..ENCODED_LEN].try_into().unwrap())?,
+            result: result_from_byte(bytes[ENCODED_LEN])?,
+            score: i32::from_le_bytes(bytes[ENCODED_LEN + 1..].try_into().unwrap()),
+        })
+    }
+}
+
+/// Writes a stream header and then one `LabelledPosition` per `write` call.
+pub struct PositionWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> PositionWriter<W> {
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&STREAM_MAGIC)?;
+        Ok(PositionWriter { inner })
+    }
+
+    pub fn write(&mut self, entry: &LabelledPosition) -> io::Result<()> {
+        self.inner.write_all(&entry.to_bytes())
+    }
+
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Iterates over the entries of a stream made by `PositionWriter`. Invalid
+/// entries are reported as `InvalidData` errors wrapping a `DecodeError`.
+pub struct PositionReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> PositionReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut magic = [0; STREAM_MAGIC.len()];
+        inner.read_exact(&mut magic)?;
+        if magic != STREAM_MAGIC {
+            return Err(DecodeError::BadMagic.into());
+        }
+        Ok(PositionReader { inner })
+    }
+}
+
+impl<R: Read> Iterator for PositionReader<R> {
+    type Item = io::Result<LabelledPosition>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; LABELLED_LEN];
+        let mut filled = 0;
+        while filled < LABELLED_LEN {
+            match self.inner.read(&mut bytes[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(io::ErrorKind::UnexpectedEof.into())),
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        Some(LabelledPosition::from_bytes(&bytes).map_err(io::Error::from))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simple::SimpleBoard;
+    use std::io::Cursor;
+
+    fn positions() -> Vec<Position> {
+        let mut board = SimpleBoard::init();
+        let mut positions = vec![Position::from_board(&board)];
+        for mov in ["D2E3", "B9C7", "C4C4", "F6F5", "E3C5", "C5C5"] {
+            board.do_move(&mov.parse().unwrap());
+            positions.push(Position::from_board(&board));
+        }
+        positions
+    }
+
+    #[test]
+    fn round_trip() {
+        for position in positions() {
+            let bytes = position.to_bytes();
+            assert_eq!(Position::from_bytes(&bytes), Ok(position));
+            let bitboard = BitBoard::from_bytes(&bytes).unwrap();
+            assert!(bitboard == BitBoard::from_position(&position));
+            assert_eq!(bitboard.to_bytes(), bytes);
+        }
+        let player_two = Position::from_board(&SimpleBoard::init().invert());
+        assert_eq!(player_two.to_bytes()[7] & 0x80, 0x80);
+    }
+
+    #[test]
+    fn rejects_corrupt_bytes() {
+        let bytes = Position::from_board(&SimpleBoard::init()).to_bytes();
+        let mut two_kinds = bytes;
+        // Mark D1, the player one king, as a bishop too.
+        two_kinds[16] |= 1 << 3;
+        assert_eq!(
+            Position::from_bytes(&two_kinds),
+            Err(DecodeError::Invalid(ValidationError::KindOverlap(3)))
+        );
+        let mut stray_player = bytes;
+        stray_player[8 + 3] |= 1;
+        assert_eq!(
+            Position::from_bytes(&stray_player),
+            Err(DecodeError::Invalid(ValidationError::OrphanedPlayer(24)))
+        );
+        let mut off_board = bytes;
+        off_board[55] |= 0x80;
+        assert_eq!(
+            Position::from_bytes(&off_board),
+            Err(DecodeError::Invalid(ValidationError::OffBoard))
+        );
+        let mut two_kings = bytes;
+        // Turn the B1 knight into a second king.
+        two_kings[32] &= !(1 << 1);
+        two_kings[24] |= 1 << 1;
+        assert_eq!(
+            Position::from_bytes(&two_kings),
+            Err(DecodeError::Invalid(ValidationError::TooManyKings(
+                Player::PlayerOne
+            )))
+        );
+    }
+
+    #[test]
+    fn stream_round_trip() {
+        let entries: Vec<LabelledPosition> = positions()
+            .into_iter()
+            .zip(
+                [
+                    GameResult::Win(Player::PlayerOne),
+                    GameResult::Draw,
+                    GameResult::Unfinished,
+                ]
+                .iter()
+                .cycle(),
+            )
+            .enumerate()
+            .map(|(idx, (position, &result))| LabelledPosition {
+                position,
+                result,
+                score: idx as i32 * -7,
+            })
+            .collect();
+        let mut writer = PositionWriter::new(vec![]).unwrap();
+        for entry in &entries {
+            writer.write(entry).unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+        assert_eq!(
+            bytes.len(),
+            STREAM_MAGIC.len() + entries.len() * LABELLED_LEN
+        );
+
+        let read: Vec<LabelledPosition> = PositionReader::new(Cursor::new(&bytes))
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, entries);
+
+        let truncated = PositionReader::new(Cursor::new(&bytes[..bytes.len() - 1])).unwrap();
+        assert_eq!(
+            truncated.last().unwrap().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        let mut bad_result = bytes.clone();
+        bad_result[STREAM_MAGIC.len() + ENCODED_LEN] = 9;
+        let err = PositionReader::new(Cursor::new(&bad_result))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(PositionReader::new(Cursor::new(&bytes[1..])).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! A game in progress: the board plus the history the draw rules need.
+//!
+//! Under the standard rules a position can never repeat: every quiet move goes
+//! forward and every capture or explosion removes material. The repetition
+//! rule is kept for variants and for games seeded with an earlier history.
+
+use crate::board::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DrawReason {
+    Repetition,
+    NoProgress,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Win(Player),
+    Draw(DrawReason),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DrawRules {
+    /// Occurrences of the same position (and side to move) that draw the game.
+    /// Never reached under the standard rules, where no position can repeat.
+    pub repetitions: usize,
+    /// Plies in a row without a capture or explosion that draw the game.
+    pub no_progress_limit: u16,
+}
+
+impl Default for DrawRules {
+    fn default() -> Self {
+        DrawRules {
+            repetitions: 3,
+            no_progress_limit: 100,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Game<M: Mov + Clone, B: Board<M>> {
+    board: B,
+    rules: DrawRules,
+    /// Hash of every position reached, including the current one.
+    history: Vec<u64>,
+    /// Each move played, how to undo it, and the no-progress count before it.
+    moves: Vec<(M, Undo, u16)>,
+    no_progress: u16,
+}
+
+impl<M: Mov + Clone, B: Board<M>> Game<M, B> {
+    pub fn new(board: B) -> Self {
+        Self::with_rules(board, DrawRules::default())
+    }
+
+    pub fn with_rules(board: B, rules: DrawRules) -> Self {
+        Game {
+            history: vec![board.get_hash()],
+            board,
+            rules,
+            moves: vec![],
+            no_progress: 0,
+        }
+    }
+
+    pub fn board(&self) -> &B {
+        &self.board
+    }
+
+    pub fn rules(&self) -> &DrawRules {
+        &self.rules
+    }
+
+    pub fn history(&self) -> &[u64] {
+        &self.history
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = &M> + '_ {
+        self.moves.iter().map(|(mov, _, _)| mov)
+    }
+
+    /// Plies since the last capture or explosion.
+    pub fn no_progress(&self) -> u16 {
+        self.no_progress
+    }
+
+    /// How many times the current position has occurred.
+    pub fn repetitions(&self) -> usize {
+        let hash = self.board.get_hash();
+        self.history.iter().filter(|&&seen| seen == hash).count()
+    }
+
+    pub fn play(&mut self, mov: &M) -> Result<(), MoveError> {
+        self.board.check_move(mov)?;
+        let undo = self.board.make_move(mov);
+        self.moves.push((*mov, undo, self.no_progress));
+        self.no_progress = match undo.removed().next() {
+            Some(_) => 0,
+            None => self.no_progress.saturating_add(1),
+        };
+        self.history.push(self.board.get_hash());
+        Ok(())
+    }
+
+    /// Takes back the last move, returning it.
+    pub fn undo(&mut self) -> Option<M> {
+        let (mov, undo, no_progress) = self.moves.pop()?;
+        self.board.undo_move(&mov, &undo);
+        self.history.pop();
+        self.no_progress = no_progress;
+        Some(mov)
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        if let Some(winner) = self.board.get_winner() {
+            return Some(Outcome::Win(winner));
+        }
+        if self.repetitions() >= self.rules.repetitions {
+            return Some(Outcome::Draw(DrawReason::Repetition));
+        }
+        if self.no_progress >= self.rules.no_progress_limit {
+            return Some(Outcome::Draw(DrawReason::NoProgress));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitboard::BitBoard;
+    use crate::simple::SimpleBoard;
+    use std::str::FromStr;
+
+    fn play_all<M, B>(game: &mut Game<M, B>, moves: &str)
+    where
+        M: Mov + Clone + FromStr<Err = MoveError>,
+        B: Board<M>,
+    {
+        for mov in moves.split_whitespace() {
+            game.play(&mov.parse().unwrap()).unwrap();
+        }
+    }
+
+    fn check_repetition<M, B>(board: B)
+    where
+        M: Mov + Clone + FromStr<Err = MoveError>,
+        B: Board<M>,
+    {
+        // Quiet moves never retreat under the standard rules, so seed the
+        // history as if the current position had been reached before.
+        let mut game = Game::new(board);
+        play_all(&mut game, "B1C3 B9C7");
+        let repeated = game.board().get_hash();
+        game.history.insert(0, repeated);
+        assert_eq!(game.repetitions(), 2);
+        assert_eq!(game.outcome(), None);
+        game.history.insert(0, repeated);
+        assert_eq!(game.repetitions(), 3);
+        assert_eq!(game.outcome(), Some(Outcome::Draw(DrawReason::Repetition)));
+        play_all(&mut game, "C3D5");
+        assert_eq!(game.outcome(), None);
+        game.undo();
+        assert_eq!(game.outcome(), Some(Outcome::Draw(DrawReason::Repetition)));
+    }
+
+    fn check_no_progress<M, B>(board: B)
+    where
+        M: Mov + Clone + FromStr<Err = MoveError>,
+        B: Board<M>,
+    {
+        let rules = DrawRules {
+            repetitions: 3,
+            no_progress_limit: 4,
+        };
+        let mut game = Game::with_rules(board, rules);
+        play_all(&mut game, "A4A5 G6G5 C4C5");
+        assert_eq!(game.no_progress(), 3);
+        assert_eq!(game.outcome(), None);
+        play_all(&mut game, "E6E5");
+        assert_eq!(game.outcome(), Some(Outcome::Draw(DrawReason::NoProgress)));
+        game.undo();
+        assert_eq!(game.no_progress(), 3);
+        play_all(&mut game, "A6A6");
+        assert_eq!(game.no_progress(), 0);
+        assert_eq!(game.outcome(), None);
+        play_all(&mut game, "E4E5");
+        assert_eq!(game.no_progress(), 1);
+        game.undo();
+        assert_eq!(game.no_progress(), 0);
+    }
+
+    #[test]
+    fn repetition_draw() {
+        check_repetition(SimpleBoard::init());
+        check_repetition(BitBoard::init());
+    }
+
+    #[test]
+    fn no_progress_draw() {
+        check_no_progress(SimpleBoard::init());
+        check_no_progress(BitBoard::init());
+    }
+
+    #[test]
+    fn rejects_illegal_moves() {
+        let mut game = Game::new(SimpleBoard::init());
+        assert_eq!(
+            game.play(&"D2D3".parse().unwrap()),
+            Err(MoveError::InvalidMove)
+        );
+        assert_eq!(game.history().len(), 1);
+        assert_eq!(game.moves().count(), 0);
+    }
+}
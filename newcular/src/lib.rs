@@ -1,6 +1,20 @@
 pub mod bitboard;
 pub mod board;
+pub mod conformance;
+pub mod encoding;
+pub mod game;
+pub mod perft;
+pub mod position;
+pub mod record;
+pub mod rules;
+#[cfg(feature = "serde")]
+mod serde_impls;
 pub mod simple;
+pub mod square;
+mod tables;
+#[cfg(test)]
+mod test_support;
+pub mod zobrist;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -10,8 +24,10 @@ pub fn add(left: usize, right: usize) -> usize {
 mod tests {
     use super::*;
     use crate::bitboard::{BitBoard, BitBoardMove};
-    use crate::board::{Board, Mov};
+    use crate::board::{Board, Mov, MoveError, MoveList, Player};
+    use crate::rules::{RuleSet, Setup};
     use crate::simple::SimpleBoard;
+    use crate::test_support::sq;
     use rand::prelude::*;
     use std::fmt::Display;
 
@@ -31,6 +47,157 @@ mod tests {
         moves
     }
 
+    fn check_make_undo<M: Mov, B: Board<M> + Clone + PartialEq>(board: &B) {
+        for mov in board.get_moves() {
+            let mut done = board.clone();
+            done.do_move(&mov);
+            let mut made = board.clone();
+            let undo = made.make_move(&mov);
+            assert!(made == done, "make_move {} differs from do_move", mov);
+            made.undo_move(&mov, &undo);
+            assert!(made == *board, "undo of {} did not restore the board", mov);
+            assert_eq!(made.get_hash(), board.get_hash());
+        }
+    }
+
+    fn check_move_errors<M, B>(board: &B)
+    where
+        M: Mov + std::str::FromStr<Err = MoveError>,
+        B: Board<M>,
+    {
+        let check = |s: &str| board.check_move(&s.parse::<M>().unwrap());
+        assert_eq!(check("D2E3"), Ok(()));
+        assert_eq!(check("D3D3"), Ok(()));
+        assert_eq!(check("D4D5"), Err(MoveError::NoSuchPiece));
+        assert_eq!(check("D8D7"), Err(MoveError::NotYourPiece));
+        assert_eq!(check("D2D3"), Err(MoveError::InvalidMove));
+        assert_eq!(check("C1C5"), Err(MoveError::InvalidMove));
+    }
+
+    #[test]
+    fn test_check_move() {
+        check_move_errors(&SimpleBoard::init());
+        check_move_errors(&BitBoard::init());
+    }
+
+    #[test]
+    fn test_try_do_move() {
+        let mut simple = SimpleBoard::init();
+        let before = simple;
+        assert_eq!(
+            simple.try_do_move(&"D2D3".parse().unwrap()),
+            Err(MoveError::InvalidMove)
+        );
+        assert_eq!(
+            simple.try_do_move(&"D8D7".parse().unwrap()),
+            Err(MoveError::NotYourPiece)
+        );
+        assert!(simple == before);
+        assert_eq!(simple.try_do_move(&"D2E3".parse().unwrap()), Ok(()));
+        assert!(simple != before);
+
+        let mut bitboard = BitBoard::init();
+        let before = bitboard;
+        assert_eq!(
+            bitboard.try_do_move(&"A5A6".parse().unwrap()),
+            Err(MoveError::NoSuchPiece)
+        );
+        assert!(bitboard == before);
+        assert_eq!(bitboard.try_do_move(&"A4A5".parse().unwrap()), Ok(()));
+        assert!(bitboard != before);
+    }
+
+    #[test]
+    fn test_move_list() {
+        let mut moves = MoveList::new();
+        BitBoard::init().get_moves_into(&mut moves);
+        assert_eq!(moves.to_vec(), BitBoard::init().get_moves());
+        let len = moves.len();
+        BitBoard::init().invert().get_moves_into(&mut moves);
+        assert_eq!(moves[len..].to_vec(), BitBoard::init().invert().get_moves());
+        moves.clear();
+        assert!(moves.is_empty());
+
+        // A king on the far rank that may not blow itself up has no moves.
+        let rules = RuleSet {
+            self_destruct_own_king: false,
+            ..RuleSet::default()
+        };
+        let setup = Setup::new("3K3/7/7/7/7/7/7/7/3k3 1", rules).unwrap();
+        let simple = SimpleBoard::from_setup(&setup);
+        let bitboard = BitBoard::from_setup(&setup);
+        assert!(!simple.has_any_move());
+        assert!(!bitboard.has_any_move());
+        assert_eq!(simple.get_winner(), Some(Player::PlayerTwo));
+        assert_eq!(bitboard.get_winner(), Some(Player::PlayerTwo));
+        assert!(!simple.invert().has_any_move());
+        assert!(!bitboard.invert().has_any_move());
+        assert_eq!(bitboard.invert().get_winner(), Some(Player::PlayerOne));
+    }
+
+    fn moves_from<M: Mov, B: Board<M>>(board: &B, square: &str) -> Vec<String> {
+        let mut moves: Vec<String> = board
+            .get_moves_from(sq(square))
+            .iter()
+            .map(|mov| mov.to_string())
+            .collect();
+        moves.sort();
+        moves
+    }
+
+    #[test]
+    fn test_get_moves_from() {
+        for moves in [
+            moves_from(&SimpleBoard::init(), "D2"),
+            moves_from(&BitBoard::init(), "D2"),
+        ] {
+            assert_eq!(
+                moves,
+                ["D2A5", "D2B4", "D2C3", "D2D2", "D2E3", "D2F4", "D2G5"]
+            );
+        }
+        // Enemy pieces and empty squares have no moves.
+        assert!(moves_from(&SimpleBoard::init(), "D8").is_empty());
+        assert!(moves_from(&BitBoard::init(), "D8").is_empty());
+        assert!(moves_from(&BitBoard::init(), "D5").is_empty());
+        assert_eq!(
+            moves_from(&BitBoard::init().invert(), "D8"),
+            moves_from(&SimpleBoard::init().invert(), "D8")
+        );
+    }
+
+    #[test]
+    fn test_make_undo() {
+        // Search makes one of these per node, so keep it small.
+        assert_eq!(std::mem::size_of::<crate::board::Undo>(), 48);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for _ in 0..300 {
+            let mut simple = SimpleBoard::init();
+            let mut bitboard = BitBoard::init();
+
+            while simple.get_winner().is_none() {
+                check_make_undo(&simple);
+                check_make_undo(&bitboard);
+
+                let picked_move = *simple.get_moves().choose(&mut rng).unwrap();
+                simple.do_move(&picked_move);
+                let (from, dest) = picked_move.get_from_dest();
+                bitboard.do_move(&BitBoardMove::from_from_dest(from, dest));
+            }
+        }
+    }
+
+    #[test]
+    fn test_conformance() {
+        conformance::check_board_against(&SimpleBoard::init(), &BitBoard::init(), 100, 5).unwrap();
+        conformance::check_board(&BitBoard::init(), 300, 5).unwrap();
+        let midgame = "2rkr2/7/n6/p1p4/B4nR/P5P/4N2/1b5/1N1K3 1";
+        conformance::check_board(&BitBoard::parse_position(midgame).unwrap(), 100, 6).unwrap();
+        let player_two = "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 2";
+        conformance::check_board(&BitBoard::parse_position(player_two).unwrap(), 100, 7).unwrap();
+    }
+
     #[test]
     fn test_board_equivalence() {
         // version stability not important
@@ -64,12 +231,50 @@ mod tests {
                     bitboard.to_string()
                 );
 
+                assert_eq!(
+                    simple.get_hash(),
+                    bitboard.get_hash(),
+                    "hashes not eq after {:?}",
+                    moves.join(" ")
+                );
+
+                let simple_inv = simple.invert();
+                let bitboard_inv = bitboard.invert();
+                assert_eq!(
+                    simple_inv.to_position(),
+                    bitboard_inv.to_position(),
+                    "inverted boards not eq after {:?}",
+                    moves.join(" ")
+                );
+                assert!(simple_inv.invert() == simple);
+                assert!(bitboard_inv.invert() == bitboard);
+                assert_eq!(
+                    moves_sorted(&simple_inv),
+                    moves_sorted(&bitboard_inv),
+                    "inverted movegen not eq after {:?}",
+                    moves.join(" ")
+                );
+                let mut simple_inv_moves: Vec<String> = simple_inv
+                    .get_moves()
+                    .into_iter()
+                    .map(|mov| mov.invert().to_string())
+                    .collect();
+                simple_inv_moves.sort();
+                assert_eq!(simple_inv_moves, simple_moves);
+                let mut bitboard_inv_moves: Vec<String> = bitboard_inv
+                    .get_moves()
+                    .into_iter()
+                    .map(|mov| mov.invert().to_string())
+                    .collect();
+                bitboard_inv_moves.sort();
+                assert_eq!(bitboard_inv_moves, bitboard_moves);
+
                 let simple_moves = simple.get_moves();
                 let picked_move = simple_moves.choose(&mut rng).unwrap();
                 moves.push(picked_move.to_string());
                 simple.do_move(picked_move);
                 let (from, dest) = picked_move.get_from_dest();
-                bitboard.do_move(&BitBoardMove::from_from_dest(from, dest).unwrap());
+                bitboard.do_move(&BitBoardMove::from_from_dest(from, dest));
             }
         }
     }
@@ -0,0 +1,128 @@
+//! Perft: counts the leaf nodes of the move tree to a fixed depth, for
+//! checking move generation against known totals.
+//!
+//! A position with a winner has no moves, so games that end early contribute
+//! nothing to the deeper counts.
+
+use crate::board::*;
+
+pub fn perft<M: Mov, B: Board<M> + Clone>(board: &B, depth: u8) -> u64 {
+    perft_inner(&mut board.clone(), depth)
+}
+
+/// The perft count below each legal move, in `get_moves` order.
+pub fn perft_divide<M: Mov, B: Board<M> + Clone>(board: &B, depth: u8) -> Vec<(M, u64)> {
+    if depth == 0 || board.get_winner().is_some() {
+        return vec![];
+    }
+    let mut board = board.clone();
+    board
+        .get_moves()
+        .into_iter()
+        .map(|mov| {
+            let undo = board.make_move(&mov);
+            let nodes = perft_inner(&mut board, depth - 1);
+            board.undo_move(&mov, &undo);
+            (mov, nodes)
+        })
+        .collect()
+}
+
+fn perft_inner<M: Mov, B: Board<M>>(board: &mut B, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if board.get_winner().is_some() {
+        return 0;
+    }
+    let moves = board.get_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut nodes = 0;
+    for mov in moves {
+        let undo = board.make_move(&mov);
+        nodes += perft_inner(board, depth - 1);
+        board.undo_move(&mov, &undo);
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitboard::BitBoard;
+    use crate::position::START_POSITION;
+    use crate::simple::SimpleBoard;
+    use crate::test_support::KING_BY_BISHOP;
+
+    /// Positions with node counts at depths 1, 2, 3, ...
+    const PERFT_SUITE: &[(&str, &[u64])] = &[
+        (START_POSITION, &[31, 831, 21154, 537846]),
+        // A thinned-out endgame with rooks on an open back rank.
+        (
+            "7/3k3/4n2/7/3Pr1p/4P1P/N4KN/7/2R1R2 2",
+            &[12, 232, 2254, 39794],
+        ),
+        // A bishop next to the king it can take out with an explosion.
+        (KING_BY_BISHOP, &[16, 225, 2612, 38878]),
+        (
+            "2r2n1/2k1r2/n2bB2/p3p1p/4P2/P1P1B1P/7/3b3/1NRK3 1",
+            &[30, 693, 15292, 326360],
+        ),
+        (
+            "2rkr2/7/n6/p1p4/B4nR/P5P/4N2/1b5/1N1K3 1",
+            &[26, 638, 12966, 291157],
+        ),
+    ];
+
+    #[test]
+    fn perft_suite_simple() {
+        for (position, counts) in PERFT_SUITE {
+            let board = SimpleBoard::parse_position(position).unwrap();
+            for (depth, &count) in (1..).zip(counts.iter()) {
+                assert_eq!(perft(&board, depth), count, "{position} at depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn perft_suite_bitboard() {
+        for (position, counts) in PERFT_SUITE {
+            let board = BitBoard::parse_position(position).unwrap();
+            for (depth, &count) in (1..).zip(counts.iter()) {
+                assert_eq!(perft(&board, depth), count, "{position} at depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn divide_matches_perft() {
+        for (position, _) in PERFT_SUITE {
+            let simple = SimpleBoard::parse_position(position).unwrap();
+            let bitboard = BitBoard::parse_position(position).unwrap();
+            let mut simple_divide: Vec<(String, u64)> = perft_divide(&simple, 3)
+                .into_iter()
+                .map(|(mov, nodes)| (mov.to_string(), nodes))
+                .collect();
+            let mut bitboard_divide: Vec<(String, u64)> = perft_divide(&bitboard, 3)
+                .into_iter()
+                .map(|(mov, nodes)| (mov.to_string(), nodes))
+                .collect();
+            simple_divide.sort();
+            bitboard_divide.sort();
+            assert_eq!(simple_divide, bitboard_divide, "{position}");
+            let total: u64 = simple_divide.iter().map(|(_, nodes)| nodes).sum();
+            assert_eq!(total, perft(&simple, 3));
+        }
+    }
+
+    #[test]
+    fn perft_stops_at_winner() {
+        // PlayerTwo's king is already gone.
+        let board = SimpleBoard::parse_position("7/7/7/7/7/7/7/7/3K3 2").unwrap();
+        assert_eq!(perft(&board, 0), 1);
+        assert_eq!(perft(&board, 1), 0);
+        assert!(perft_divide(&board, 2).is_empty());
+    }
+}
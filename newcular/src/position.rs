@@ -0,0 +1,270 @@
+//! Text notation for positions, in the spirit of chess FEN.
+//!
+//! A position is the nine ranks from 9 down to 1 separated by `/`, then a
+//! space and the side to move (`1` or `2`). Each rank lists files A to G:
+//! PlayerOne pieces are upper case (`B`, `K`, `N`, `R`, `P`), PlayerTwo pieces
+//! are lower case, and a digit stands for that many empty squares. The
+//! starting position is
+//!
+//! ```text
+//! 1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 1
+//! ```
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::board::*;
+
+pub const START_POSITION: &str = "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 1";
+
+/// Row-major piece placement; `rows[0]` is rank 1 and `rows[r][0]` is the A file.
+pub type Grid = [[Option<(Player, PieceKind)>; 7]; 9];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PositionError {
+    /// The placement did not have exactly nine ranks; holds the count found.
+    WrongRankCount(usize),
+    /// A rank (numbered 1 to 9) did not describe exactly seven squares.
+    WrongRankLength(u8),
+    InvalidPiece(char),
+    MissingPlayer,
+    InvalidPlayer,
+    TrailingInput,
+    /// The placement gives this side more than one king.
+    TooManyKings(Player),
+}
+
+impl Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::WrongRankCount(count) => write!(f, "expected 9 ranks, found {count}"),
+            PositionError::WrongRankLength(rank) => {
+                write!(f, "rank {rank} does not have exactly 7 squares")
+            }
+            PositionError::InvalidPiece(ch) => write!(f, "invalid piece character {ch:?}"),
+            PositionError::MissingPlayer => write!(f, "missing side to move"),
+            PositionError::InvalidPlayer => write!(f, "side to move must be 1 or 2"),
+            PositionError::TrailingInput => write!(f, "unexpected input after side to move"),
+            PositionError::TooManyKings(player) => write!(f, "{player:?} has more than one king"),
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Position {
+    pub rows: Grid,
+    pub player: Player,
+}
+
+impl Position {
+    pub fn from_board<M: Mov, B: Board<M>>(board: &B) -> Position {
+        let mut rows = [[None; 7]; 9];
+        for (square, player, kind) in board.pieces() {
+            rows[square.row() as usize][square.col() as usize] = Some((player, kind));
+        }
+        Position {
+            rows,
+            player: board.get_player(),
+        }
+    }
+
+    /// A grid cannot have inconsistent squares, so this only checks that
+    /// neither side has more than one king.
+    pub fn validate(&self) -> Result<(), PositionError> {
+        for player in [Player::PlayerOne, Player::PlayerTwo] {
+            let kings = self
+                .rows
+                .iter()
+                .flatten()
+                .filter(|&&square| square == Some((player, PieceKind::K)))
+                .count();
+            if kings > 1 {
+                return Err(PositionError::TooManyKings(player));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn piece_char(player: Player, kind: PieceKind) -> char {
+    let ch = kind.letter();
+    match player {
+        Player::PlayerOne => ch,
+        Player::PlayerTwo => ch.to_ascii_lowercase(),
+    }
+}
+
+fn char_piece(ch: char) -> Option<(Player, PieceKind)> {
+    let kind = PieceKind::from_letter(ch)?;
+    match ch.is_ascii_uppercase() {
+        true => Some((Player::PlayerOne, kind)),
+        false => Some((Player::PlayerTwo, kind)),
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (row_idx, row) in self.rows.iter().enumerate().rev() {
+            let mut empty = 0;
+            for square in row {
+                match square {
+                    Some((player, kind)) => {
+                        if empty > 0 {
+                            write!(f, "{empty}")?;
+                            empty = 0;
+                        }
+                        write!(f, "{}", piece_char(*player, *kind))?;
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                write!(f, "{empty}")?;
+            }
+            if row_idx > 0 {
+                write!(f, "/")?;
+            }
+        }
+        write!(f, " {}", self.player.ord())
+    }
+}
+
+impl FromStr for Position {
+    type Err = PositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+        let placement = fields.next().unwrap_or("");
+        let player = match fields.next() {
+            Some("1") => Player::PlayerOne,
+            Some("2") => Player::PlayerTwo,
+            Some(_) => return Err(PositionError::InvalidPlayer),
+            None => return Err(PositionError::MissingPlayer),
+        };
+        if fields.next().is_some() {
+            return Err(PositionError::TrailingInput);
+        }
+
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 9 {
+            return Err(PositionError::WrongRankCount(ranks.len()));
+        }
+
+        let mut rows = [[None; 7]; 9];
+        for (rank_text, row_idx) in ranks.iter().zip((0..9usize).rev()) {
+            let bad_length = PositionError::WrongRankLength(row_idx as u8 + 1);
+            let mut col_idx = 0usize;
+            for ch in rank_text.chars() {
+                match ch {
+                    '1'..='7' => col_idx += ch as usize - '0' as usize,
+                    _ => {
+                        let piece = char_piece(ch).ok_or(PositionError::InvalidPiece(ch))?;
+                        if col_idx >= 7 {
+                            return Err(bad_length);
+                        }
+                        rows[row_idx][col_idx] = Some(piece);
+                        col_idx += 1;
+                    }
+                }
+                if col_idx > 7 {
+                    return Err(bad_length);
+                }
+            }
+            if col_idx != 7 {
+                return Err(bad_length);
+            }
+        }
+
+        let position = Position { rows, player };
+        position.validate()?;
+        Ok(position)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitboard::{BitBoard, BitBoardMove};
+    use crate::simple::SimpleBoard;
+    use rand::prelude::*;
+
+    #[test]
+    fn start_position_round_trip() {
+        assert_eq!(SimpleBoard::init().to_position(), START_POSITION);
+        assert_eq!(BitBoard::init().to_position(), START_POSITION);
+        assert!(SimpleBoard::parse_position(START_POSITION).unwrap() == SimpleBoard::init());
+        assert!(BitBoard::parse_position(START_POSITION).unwrap() == BitBoard::init());
+    }
+
+    #[test]
+    fn playout_round_trip() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..200 {
+            let mut simple = SimpleBoard::init();
+            let mut bitboard = BitBoard::init();
+
+            while simple.get_winner().is_none() {
+                let position = simple.to_position();
+                assert_eq!(position, bitboard.to_position());
+                assert!(SimpleBoard::parse_position(&position).unwrap() == simple);
+                assert!(BitBoard::parse_position(&position).unwrap() == bitboard);
+                assert_eq!(position.parse::<Position>().unwrap().to_string(), position);
+
+                let picked_move = *simple.get_moves().choose(&mut rng).unwrap();
+                simple.do_move(&picked_move);
+                let (from, dest) = picked_move.get_from_dest();
+                bitboard.do_move(&BitBoardMove::from_from_dest(from, dest));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3 1".parse::<Position>(),
+            Err(PositionError::WrongRankCount(8))
+        );
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/4B3/1NRKRN1 1".parse::<Position>(),
+            Err(PositionError::WrongRankLength(2))
+        );
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/6/P1P1P1P/3B3/3B3/1NRKRN1 1".parse::<Position>(),
+            Err(PositionError::WrongRankLength(5))
+        );
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1Q1P/3B3/3B3/1NRKRN1 1".parse::<Position>(),
+            Err(PositionError::InvalidPiece('Q'))
+        );
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1".parse::<Position>(),
+            Err(PositionError::MissingPlayer)
+        );
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 w".parse::<Position>(),
+            Err(PositionError::InvalidPlayer)
+        );
+        assert_eq!(
+            "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 1 0".parse::<Position>(),
+            Err(PositionError::TrailingInput)
+        );
+        assert_eq!(
+            "1nrkrk1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 1".parse::<Position>(),
+            Err(PositionError::TooManyKings(Player::PlayerTwo))
+        );
+    }
+
+    #[test]
+    fn side_to_move() {
+        let position = "1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 2";
+        let simple = SimpleBoard::parse_position(position).unwrap();
+        let bitboard = BitBoard::parse_position(position).unwrap();
+        assert_eq!(simple.get_player(), Player::PlayerTwo);
+        assert_eq!(bitboard.get_player(), Player::PlayerTwo);
+        assert_eq!(simple.to_position(), position);
+        assert_eq!(bitboard.to_position(), position);
+    }
+}
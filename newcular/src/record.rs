@@ -0,0 +1,539 @@
+//! A PGN-like text format for finished or in-progress games.
+//!
+//! ```text
+//! [Event "Team Ranked Tourney"]
+//! [Round "3"]
+//! [PlayerOne "Kled"]
+//! [PlayerTwo "Boardem"]
+//! [ExplosionRadius "1"]
+//! [SelfDestructOwnKing "true"]
+//! [Repetitions "3"]
+//! [NoProgressLimit "100"]
+//! [Result "1-0"]
+//!
+//! 1. D2E3 {opens the bishop} B9C7 2. E3F4 ... 1-0
+//! ```
+//!
+//! `Position` is only written for games that do not start from the standard
+//! position. Moves use coordinate notation; a comment in braces belongs to the
+//! move before it.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::board::*;
+use crate::game::{DrawRules, Game, Outcome};
+use crate::position::{Position, PositionError, START_POSITION};
+use crate::rules::Setup;
+use crate::simple::{SimpleBoard, SimpleMove};
+
+/// Tags that map onto `GameRecord` fields rather than `GameRecord::tags`.
+const RESERVED_TAGS: [&str; 6] = [
+    "ExplosionRadius",
+    "SelfDestructOwnKing",
+    "Repetitions",
+    "NoProgressLimit",
+    "Position",
+    "Result",
+];
+
+/// Movetext lines are wrapped before this many characters.
+const LINE_WIDTH: usize = 80;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameResult {
+    Win(Player),
+    Draw,
+    /// Still in progress, abandoned, or otherwise unknown.
+    Unfinished,
+}
+
+impl From<Option<Outcome>> for GameResult {
+    fn from(outcome: Option<Outcome>) -> Self {
+        match outcome {
+            Some(Outcome::Win(player)) => GameResult::Win(player),
+            Some(Outcome::Draw(_)) => GameResult::Draw,
+            None => GameResult::Unfinished,
+        }
+    }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameResult::Win(Player::PlayerOne) => write!(f, "1-0"),
+            GameResult::Win(Player::PlayerTwo) => write!(f, "0-1"),
+            GameResult::Draw => write!(f, "1/2-1/2"),
+            GameResult::Unfinished => write!(f, "*"),
+        }
+    }
+}
+
+impl FromStr for GameResult {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1-0" => Ok(GameResult::Win(Player::PlayerOne)),
+            "0-1" => Ok(GameResult::Win(Player::PlayerTwo)),
+            "1/2-1/2" => Ok(GameResult::Draw),
+            "*" => Ok(GameResult::Unfinished),
+            _ => Err(RecordError::InvalidResult(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecordError {
+    /// A tag line that is not `[Name "value"]`.
+    InvalidTag(String),
+    InvalidPosition(PositionError),
+    /// A rule tag with a value that could not be parsed.
+    InvalidRules(String),
+    /// The move at `ply` (counting from 0) is not legal on the board.
+    InvalidMove {
+        ply: usize,
+        notation: String,
+        err: MoveError,
+    },
+    /// A move was recorded after the game had already ended.
+    MoveAfterEnd(usize),
+    InvalidResult(String),
+    /// The recorded result contradicts the final position.
+    ResultMismatch(GameResult),
+    UnterminatedComment,
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::InvalidTag(line) => write!(f, "invalid tag {line:?}"),
+            RecordError::InvalidPosition(err) => write!(f, "invalid position: {err}"),
+            RecordError::InvalidRules(value) => write!(f, "invalid rule value {value:?}"),
+            RecordError::InvalidMove { ply, notation, err } => {
+                write!(f, "invalid move {notation} at ply {ply}: {err}")
+            }
+            RecordError::MoveAfterEnd(ply) => {
+                write!(f, "move at ply {ply} comes after the game ended")
+            }
+            RecordError::InvalidResult(result) => write!(f, "invalid result {result:?}"),
+            RecordError::ResultMismatch(result) => {
+                write!(f, "result {result} does not match the final position")
+            }
+            RecordError::UnterminatedComment => write!(f, "comment is missing its closing brace"),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl From<PositionError> for RecordError {
+    fn from(err: PositionError) -> Self {
+        RecordError::InvalidPosition(err)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordedMove {
+    pub mov: SimpleMove,
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameRecord {
+    /// Free-form tags such as `Event`, `Date`, `Round`, `PlayerOne` and
+    /// `PlayerTwo`, in the order they are written.
+    pub tags: Vec<(String, String)>,
+    pub setup: Setup,
+    pub draw_rules: DrawRules,
+    /// A comment on the game as a whole, written before the first move.
+    pub comment: Option<String>,
+    pub moves: Vec<RecordedMove>,
+    pub result: GameResult,
+}
+
+impl GameRecord {
+    pub fn new(setup: Setup) -> Self {
+        GameRecord {
+            tags: vec![],
+            setup,
+            draw_rules: DrawRules::default(),
+            comment: None,
+            moves: vec![],
+            result: GameResult::Unfinished,
+        }
+    }
+
+    /// Records the moves and outcome of `game` from the position it started in.
+    pub fn from_game<M: Mov + Clone, B: Board<M> + Clone>(game: &Game<M, B>) -> Self {
+        let mut start = game.clone();
+        while start.undo().is_some() {}
+        let mut record = GameRecord::new(Setup {
+            position: Position::from_board(start.board()),
+            rules: start.board().get_rules(),
+        });
+        record.draw_rules = *game.rules();
+        for mov in game.moves() {
+            let (from, dest) = mov.get_from_dest();
+            record.moves.push(RecordedMove {
+                mov: SimpleMove::from_from_dest(from, dest),
+                comment: None,
+            });
+        }
+        record.result = game.outcome().into();
+        record
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a free-form tag, replacing any earlier value but keeping its place.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Plays the recorded moves from the setup, checking each one and the result.
+    pub fn replay(&self) -> Result<Game<SimpleMove, SimpleBoard>, RecordError> {
+        let mut game = Game::with_rules(SimpleBoard::from_setup(&self.setup), self.draw_rules);
+        for (ply, recorded) in self.moves.iter().enumerate() {
+            if game.outcome().is_some() {
+                return Err(RecordError::MoveAfterEnd(ply));
+            }
+            game.play(&recorded.mov)
+                .map_err(|err| RecordError::InvalidMove {
+                    ply,
+                    notation: recorded.mov.to_string(),
+                    err,
+                })?;
+        }
+        // A win may be recorded early by resignation, but never against the board.
+        let consistent = match (game.outcome(), self.result) {
+            (Some(Outcome::Win(winner)), result) => result == GameResult::Win(winner),
+            (_, GameResult::Win(_)) => game.board().get_winner().is_none(),
+            _ => true,
+        };
+        match consistent {
+            true => Ok(game),
+            false => Err(RecordError::ResultMismatch(self.result)),
+        }
+    }
+}
+
+fn write_tag(f: &mut std::fmt::Formatter<'_>, name: &str, value: &str) -> std::fmt::Result {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{name} \"{escaped}\"]")
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.tags {
+            if RESERVED_TAGS.contains(&name.as_str()) {
+                continue;
+            }
+            write_tag(f, name, value)?;
+        }
+        write_tag(
+            f,
+            "ExplosionRadius",
+            &self.setup.rules.explosion_radius.to_string(),
+        )?;
+        write_tag(
+            f,
+            "SelfDestructOwnKing",
+            &self.setup.rules.self_destruct_own_king.to_string(),
+        )?;
+        write_tag(f, "Repetitions", &self.draw_rules.repetitions.to_string())?;
+        write_tag(
+            f,
+            "NoProgressLimit",
+            &self.draw_rules.no_progress_limit.to_string(),
+        )?;
+        let position = self.setup.position.to_string();
+        if position != START_POSITION {
+            write_tag(f, "Position", &position)?;
+        }
+        write_tag(f, "Result", &self.result.to_string())?;
+        writeln!(f)?;
+
+        let mut tokens: Vec<String> = self
+            .comment
+            .iter()
+            .map(|comment| format!("{{{comment}}}"))
+            .collect();
+        let mut player = self.setup.position.player;
+        for (ply, recorded) in self.moves.iter().enumerate() {
+            let number = 1 + (ply + (player == Player::PlayerTwo) as usize) / 2;
+            match player {
+                Player::PlayerOne => tokens.push(format!("{number}.")),
+                Player::PlayerTwo if ply == 0 => tokens.push(format!("{number}...")),
+                Player::PlayerTwo => {}
+            }
+            tokens.push(recorded.mov.to_string());
+            if let Some(comment) = &recorded.comment {
+                tokens.push(format!("{{{comment}}}"));
+            }
+            player = player.other();
+        }
+        tokens.push(self.result.to_string());
+
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > LINE_WIDTH {
+                writeln!(f)?;
+                line_len = 0;
+            }
+            if line_len > 0 {
+                write!(f, " ")?;
+                line_len += 1;
+            }
+            write!(f, "{token}")?;
+            line_len += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+fn parse_tag(line: &str) -> Result<(String, String), RecordError> {
+    let invalid = || RecordError::InvalidTag(line.to_string());
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(invalid)?;
+    let (name, value) = inner.split_once(char::is_whitespace).ok_or_else(invalid)?;
+    let quoted = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    let mut unescaped = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => unescaped.push(chars.next().ok_or_else(invalid)?),
+            '"' => return Err(invalid()),
+            _ => unescaped.push(ch),
+        }
+    }
+    Ok((name.to_string(), unescaped))
+}
+
+fn is_move_number(token: &str) -> bool {
+    let digits = token.trim_end_matches('.');
+    digits.len() < token.len() && !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    /// Parses a record and replays it, so every move is checked against the board.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut record = GameRecord::new(Setup::default());
+        let mut tag_result = None;
+        let mut lines = s.lines().map(str::trim).peekable();
+        while let Some(line) = lines.next_if(|line| line.is_empty() || line.starts_with('[')) {
+            if line.is_empty() {
+                continue;
+            }
+            let (name, value) = parse_tag(line)?;
+            match name.as_str() {
+                "ExplosionRadius" => {
+                    record.setup.rules.explosion_radius = value
+                        .parse()
+                        .map_err(|_| RecordError::InvalidRules(value.clone()))?;
+                }
+                "SelfDestructOwnKing" => {
+                    record.setup.rules.self_destruct_own_king = value
+                        .parse()
+                        .map_err(|_| RecordError::InvalidRules(value.clone()))?;
+                }
+                "Repetitions" => {
+                    record.draw_rules.repetitions = value
+                        .parse()
+                        .map_err(|_| RecordError::InvalidRules(value.clone()))?;
+                }
+                "NoProgressLimit" => {
+                    record.draw_rules.no_progress_limit = value
+                        .parse()
+                        .map_err(|_| RecordError::InvalidRules(value.clone()))?;
+                }
+                "Position" => record.setup.position = value.parse()?,
+                "Result" => tag_result = Some(value.parse::<GameResult>()?),
+                _ => record.set_tag(&name, &value),
+            }
+        }
+
+        let movetext: String = lines.collect::<Vec<_>>().join("\n");
+        let mut rest = movetext.trim_start();
+        let mut end_result = None;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('{') {
+                let (comment, after) = after
+                    .split_once('}')
+                    .ok_or(RecordError::UnterminatedComment)?;
+                let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                match record.moves.last_mut() {
+                    Some(last) => last.comment = Some(comment),
+                    None => record.comment = Some(comment),
+                }
+                rest = after.trim_start();
+                continue;
+            }
+            let end = rest
+                .find(|ch: char| ch.is_whitespace() || ch == '{')
+                .unwrap_or(rest.len());
+            let (token, after) = rest.split_at(end);
+            rest = after.trim_start();
+            if end_result.is_some() {
+                return Err(RecordError::InvalidResult(token.to_string()));
+            }
+            if is_move_number(token) {
+                continue;
+            }
+            if let Ok(result) = token.parse::<GameResult>() {
+                end_result = Some(result);
+                continue;
+            }
+            let mov = token.parse().map_err(|err| RecordError::InvalidMove {
+                ply: record.moves.len(),
+                notation: token.to_string(),
+                err,
+            })?;
+            record.moves.push(RecordedMove { mov, comment: None });
+        }
+
+        record.result = match (tag_result, end_result) {
+            (Some(tag), Some(end)) if tag != end => return Err(RecordError::ResultMismatch(end)),
+            (tag, end) => tag.or(end).unwrap_or(GameResult::Unfinished),
+        };
+        record.replay()?;
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitboard::{BitBoard, BitBoardMove};
+    use crate::rules::RuleSet;
+
+    /// PlayerOne's rook can take the king straight up the D file.
+    const ROOK_MATE: &str = "3k3/7/7/7/7/7/7/3R3/3K3 1";
+
+    fn with_moves(moves: &str) -> Game<BitBoardMove, BitBoard> {
+        let mut game = Game::new(BitBoard::init());
+        for mov in moves.split_whitespace() {
+            game.play(&mov.parse().unwrap()).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut record = GameRecord::from_game(&with_moves("D2E3 B9C7 E3F4 C7D5"));
+        record.set_tag("Event", "Team Ranked Tourney");
+        record.set_tag("PlayerOne", "The \"Kled\" team");
+        record.set_tag("Round", "3");
+        record.moves[1].comment = Some("develops the knight".to_string());
+        let text = record.to_string();
+        assert_eq!(
+            text,
+            "[Event \"Team Ranked Tourney\"]\n\
+             [PlayerOne \"The \\\"Kled\\\" team\"]\n\
+             [Round \"3\"]\n\
+             [ExplosionRadius \"1\"]\n\
+             [SelfDestructOwnKing \"true\"]\n\
+             [Repetitions \"3\"]\n\
+             [NoProgressLimit \"100\"]\n\
+             [Result \"*\"]\n\
+             \n\
+             1. D2E3 B9C7 {develops the knight} 2. E3F4 C7D5 *\n"
+        );
+        assert_eq!(text.parse::<GameRecord>(), Ok(record.clone()));
+        assert_eq!(record.tag("PlayerOne"), Some("The \"Kled\" team"));
+        let replayed = record.replay().unwrap();
+        assert_eq!(
+            replayed.board().to_position(),
+            with_moves("D2E3 B9C7 E3F4 C7D5").board().to_position()
+        );
+    }
+
+    #[test]
+    fn custom_setup() {
+        let setup = Setup::new(
+            "3k3/7/7/7/7/7/7/3R3/3K3 2",
+            RuleSet {
+                explosion_radius: 2,
+                ..RuleSet::default()
+            },
+        );
+        let mut record = GameRecord::new(setup.unwrap());
+        record.draw_rules.no_progress_limit = 40;
+        record.moves.push(RecordedMove {
+            mov: "D9C8".parse().unwrap(),
+            comment: None,
+        });
+        let text = record.to_string();
+        assert!(text.contains("[ExplosionRadius \"2\"]"));
+        assert!(text.contains("[NoProgressLimit \"40\"]"));
+        assert!(text.contains("[Position \"3k3/7/7/7/7/7/7/3R3/3K3 2\"]"));
+        assert!(text.ends_with("1... D9C8 *\n"));
+        assert_eq!(text.parse::<GameRecord>(), Ok(record));
+    }
+
+    #[test]
+    fn validates_moves_and_result() {
+        let tags = format!("[Position \"{ROOK_MATE}\"]\n\n");
+        let parse = |movetext: &str| format!("{tags}{movetext}").parse::<GameRecord>();
+        assert_eq!(
+            parse("1. D2D9 1-0").unwrap().result,
+            GameResult::Win(Player::PlayerOne)
+        );
+        assert_eq!(
+            parse("1. D2D9 0-1"),
+            Err(RecordError::ResultMismatch(GameResult::Win(
+                Player::PlayerTwo
+            )))
+        );
+        assert_eq!(
+            parse("1. D2D9 *"),
+            Err(RecordError::ResultMismatch(GameResult::Unfinished))
+        );
+        assert_eq!(parse("1. D2D9 D9D8 1-0"), Err(RecordError::MoveAfterEnd(1)));
+        assert_eq!(
+            "[NoProgressLimit \"2\"]\n\n1. A4A5 G6G5 2. C4C5 *".parse::<GameRecord>(),
+            Err(RecordError::MoveAfterEnd(2))
+        );
+        // Resigning before the king falls is fine.
+        assert_eq!(
+            parse("1. D2D3 0-1").unwrap().result,
+            GameResult::Win(Player::PlayerTwo)
+        );
+        assert_eq!(
+            parse("1. D2D3 D9C8 2. D1E3 *"),
+            Err(RecordError::InvalidMove {
+                ply: 2,
+                notation: "D1E3".to_string(),
+                err: MoveError::InvalidMove,
+            })
+        );
+        assert_eq!(
+            parse("1. D2D3 {unfinished"),
+            Err(RecordError::UnterminatedComment)
+        );
+        assert_eq!(
+            parse("1. D2D9 1-0 D9D8"),
+            Err(RecordError::InvalidResult("D9D8".to_string()))
+        );
+        assert_eq!(
+            "[Result 1-0]".parse::<GameRecord>(),
+            Err(RecordError::InvalidTag("[Result 1-0]".to_string()))
+        );
+    }
+}
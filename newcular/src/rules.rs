@@ -0,0 +1,133 @@
+//! Rule variations and starting setups that both boards can be built from.
+
+use crate::position::{Position, PositionError, START_POSITION};
+use crate::square::Square;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleSet {
+    /// How far a self-destruct reaches in every direction; 1 clears a 3x3 area.
+    pub explosion_radius: u8,
+    /// Whether a piece may self-destruct when its own king is in the blast.
+    pub self_destruct_own_king: bool,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            explosion_radius: 1,
+            self_destruct_own_king: true,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Whether the blast of a self-destruct at `from` reaches `target`.
+    pub fn in_blast(&self, from: Square, target: Square) -> bool {
+        from.distance(target) <= self.explosion_radius
+    }
+}
+
+/// A starting position together with the rules to play it under.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Setup {
+    pub position: Position,
+    pub rules: RuleSet,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup {
+            position: START_POSITION.parse().unwrap(),
+            rules: RuleSet::default(),
+        }
+    }
+}
+
+impl Setup {
+    pub fn new(position: &str, rules: RuleSet) -> Result<Self, PositionError> {
+        Ok(Setup {
+            position: position.parse()?,
+            rules,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitboard::BitBoard;
+    use crate::board::*;
+    use crate::conformance::check_board;
+    use crate::simple::SimpleBoard;
+
+    fn moves_sorted<M: Mov, B: Board<M>>(board: &B) -> Vec<String> {
+        let mut moves: Vec<String> = board
+            .get_moves()
+            .iter()
+            .map(|mov| mov.to_string())
+            .collect();
+        moves.sort();
+        moves
+    }
+
+    #[test]
+    fn default_setup_is_init() {
+        assert!(SimpleBoard::from_setup(&Setup::default()) == SimpleBoard::init());
+        assert!(BitBoard::from_setup(&Setup::default()) == BitBoard::init());
+    }
+
+    #[test]
+    fn wide_explosions() {
+        let rules = RuleSet {
+            explosion_radius: 2,
+            ..RuleSet::default()
+        };
+        let setup = Setup::new("3k3/7/7/7/1ppp3/1pPp3/1ppp3/7/3K3 1", rules).unwrap();
+        let mut simple = SimpleBoard::from_setup(&setup);
+        let mut bitboard = BitBoard::from_setup(&setup);
+        simple.do_move(&"C4C4".parse().unwrap());
+        bitboard.do_move(&"C4C4".parse().unwrap());
+        assert_eq!(simple.to_position(), "3k3/7/7/7/7/7/7/7/3K3 2");
+        assert_eq!(bitboard.to_position(), "3k3/7/7/7/7/7/7/7/3K3 2");
+
+        check_board(
+            &BitBoard::from_setup(&Setup {
+                rules,
+                ..Setup::default()
+            }),
+            100,
+            10,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn protected_own_king() {
+        let rules = RuleSet {
+            self_destruct_own_king: false,
+            ..RuleSet::default()
+        };
+        let setup = Setup {
+            rules,
+            ..Setup::default()
+        };
+        let simple = SimpleBoard::from_setup(&setup);
+        let bitboard = BitBoard::from_setup(&setup);
+        for moves in [moves_sorted(&simple), moves_sorted(&bitboard)] {
+            assert!(!moves.contains(&"D1D1".to_string()));
+            assert!(!moves.contains(&"D2D2".to_string()));
+            assert!(!moves.contains(&"C1C1".to_string()));
+            assert!(moves.contains(&"D3D3".to_string()));
+            assert!(moves.contains(&"B1B1".to_string()));
+        }
+        assert_eq!(
+            moves_sorted(&simple).len(),
+            SimpleBoard::init().get_moves().len() - 4
+        );
+
+        check_board(&bitboard, 100, 11).unwrap();
+        check_board(&bitboard.invert(), 100, 12).unwrap();
+    }
+}
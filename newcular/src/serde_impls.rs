@@ -0,0 +1,133 @@
+//! `Serialize` and `Deserialize` for the board types, behind the `serde`
+//! feature.
+//!
+//! Moves use coordinate notation and boards use their position string, so the
+//! representation does not depend on how a backend stores its pieces. A board
+//! string carries no rules; serialize a `Setup` when they matter.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bitboard::{BitBoard, BitBoardMove};
+use crate::position::Position;
+use crate::simple::{SimpleBoard, SimpleMove};
+
+fn serialize_str<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn deserialize_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
+
+/// Implements both traits through `Display` and `FromStr`.
+macro_rules! string_serde {
+    ($ty:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_str(self, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_str(deserializer)
+            }
+        }
+    };
+}
+
+string_serde!(SimpleMove);
+string_serde!(BitBoardMove);
+string_serde!(Position);
+
+/// Implements both traits through `to_position` and `parse_position`.
+macro_rules! position_serde {
+    ($ty:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_position())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                <$ty>::parse_position(&s).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+position_serde!(SimpleBoard);
+position_serde!(BitBoard);
+
+#[cfg(test)]
+mod test {
+    use crate::bitboard::{BitBoard, BitBoardMove};
+    use crate::board::{Board, PieceKind, Player};
+    use crate::position::START_POSITION;
+    use crate::rules::{RuleSet, Setup};
+    use crate::simple::{SimpleBoard, SimpleMove};
+
+    #[test]
+    fn moves_and_pieces() {
+        let mov: SimpleMove = "D2E3".parse().unwrap();
+        assert_eq!(serde_json::to_string(&mov).unwrap(), "\"D2E3\"");
+        assert_eq!(
+            serde_json::from_str::<BitBoardMove>("\"d2e3\"")
+                .unwrap()
+                .to_string(),
+            "D2E3"
+        );
+        assert!(serde_json::from_str::<SimpleMove>("\"D2Z3\"").is_err());
+        assert_eq!(
+            serde_json::to_string(&Player::PlayerTwo).unwrap(),
+            "\"PlayerTwo\""
+        );
+        assert_eq!(
+            serde_json::from_str::<PieceKind>("\"N\"").unwrap(),
+            PieceKind::N
+        );
+    }
+
+    #[test]
+    fn boards_as_positions() {
+        let expected = format!("\"{START_POSITION}\"");
+        assert_eq!(
+            serde_json::to_string(&SimpleBoard::init()).unwrap(),
+            expected
+        );
+        assert_eq!(serde_json::to_string(&BitBoard::init()).unwrap(), expected);
+
+        let mut board = BitBoard::init();
+        board.do_move(&"C4C4".parse().unwrap());
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(serde_json::from_str::<BitBoard>(&json).unwrap() == board);
+        let simple: SimpleBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(simple.to_position(), board.to_position());
+        assert!(serde_json::from_str::<BitBoard>("\"7/7 1\"").is_err());
+    }
+
+    #[test]
+    fn setups_keep_rules() {
+        let rules = RuleSet {
+            explosion_radius: 2,
+            self_destruct_own_king: false,
+        };
+        let setup = Setup {
+            rules,
+            ..Setup::default()
+        };
+        let json = serde_json::to_string(&setup).unwrap();
+        assert_eq!(serde_json::from_str::<Setup>(&json).unwrap(), setup);
+    }
+}
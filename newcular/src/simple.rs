@@ -1,46 +1,56 @@
 use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
 
 use crate::board::*;
+use crate::position::{Position, PositionError};
+use crate::rules::{RuleSet, Setup};
+use crate::zobrist;
 
-fn piece_rank(kind: &PieceKind) -> i32 {
-    match kind {
-        PieceKind::B => 5,
-        PieceKind::K => 50,
-        PieceKind::N => 3,
-        PieceKind::R => 5,
-        PieceKind::P => 1,
-    }
+#[derive(Clone, Copy, Eq, Debug, PartialEq, Default)]
+pub struct SimpleMove {
+    from: Square,
+    dest: Square,
 }
 
-#[derive(Clone, Copy, Eq, Debug, PartialEq)]
-pub struct SimpleMove {
-    from_rc: (u8, u8),
-    dest_rc: (u8, u8),
+impl SimpleMove {
+    pub fn from_from_dest(from: Square, dest: Square) -> SimpleMove {
+        SimpleMove { from, dest }
+    }
 }
 
 impl Mov for SimpleMove {
     fn invert(&self) -> Self {
         SimpleMove {
-            from_rc: (9 - self.from_rc.0, self.from_rc.1),
-            dest_rc: (9 - self.dest_rc.0, self.dest_rc.1),
+            from: self.from.invert(),
+            dest: self.dest.invert(),
+        }
+    }
+
+    fn mirror_horizontal(&self) -> Self {
+        SimpleMove {
+            from: self.from.mirror_horizontal(),
+            dest: self.dest.mirror_horizontal(),
         }
     }
 
-    fn get_from_dest(&self) -> ((u8, u8), (u8, u8)) {
-        (self.from_rc, self.dest_rc)
+    fn get_from_dest(&self) -> (Square, Square) {
+        (self.from, self.dest)
+    }
+}
+
+impl FromStr for SimpleMove {
+    type Err = MoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [from, dest] = parse_from_dest(s)?;
+        Ok(SimpleMove { from, dest })
     }
 }
 
 impl Display for SimpleMove {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}{}{}",
-            (b'A' + self.from_rc.1) as char,
-            self.from_rc.0 + 1,
-            (b'A' + self.dest_rc.1) as char,
-            self.dest_rc.0 + 1
-        )
+        write!(f, "{}{}", self.from, self.dest)
     }
 }
 
@@ -49,6 +59,14 @@ pub struct SimpleBoard {
     current_player: Player,
     pub rows: [[Option<(Player, PieceKind)>; 7]; 9],
     pub eval: i32,
+    hash: u64,
+    rules: RuleSet,
+}
+
+impl Hash for SimpleBoard {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
 }
 
 impl Display for SimpleBoard {
@@ -95,221 +113,228 @@ impl Display for SimpleBoard {
 
 impl SimpleBoard {
     pub fn init() -> Self {
+        Self::from_setup(&Setup::default())
+    }
+
+    pub fn from_position(position: &Position) -> Self {
+        Self::from_setup(&Setup {
+            position: *position,
+            rules: RuleSet::default(),
+        })
+    }
+
+    pub fn from_setup(setup: &Setup) -> Self {
+        let position = &setup.position;
+        let eval = position
+            .rows
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|(player, kind)| (player.parity() as i32) * kind.value())
+            .sum();
         SimpleBoard {
-            current_player: Player::PlayerOne,
-            rows: [
-                ['-', 'N', 'R', 'K', 'R', 'N', '-'],
-                ['-', '-', '-', 'B', '-', '-', '-'],
-                ['-', '-', '-', 'B', '-', '-', '-'],
-                ['P', '-', 'P', '-', 'P', '-', 'P'],
-                ['-', '-', '-', '-', '-', '-', '-'],
-                ['p', '-', 'p', '-', 'p', '-', 'p'],
-                ['-', '-', '-', 'b', '-', '-', '-'],
-                ['-', '-', '-', 'b', '-', '-', '-'],
-                ['-', 'n', 'r', 'k', 'r', 'n', '-'],
-            ]
-            .map(|row| {
-                row.map(|ch| match ch {
-                    'B' => Some((Player::PlayerOne, PieceKind::B)),
-                    'K' => Some((Player::PlayerOne, PieceKind::K)),
-                    'N' => Some((Player::PlayerOne, PieceKind::N)),
-                    'R' => Some((Player::PlayerOne, PieceKind::R)),
-                    'P' => Some((Player::PlayerOne, PieceKind::P)),
-                    'b' => Some((Player::PlayerTwo, PieceKind::B)),
-                    'k' => Some((Player::PlayerTwo, PieceKind::K)),
-                    'n' => Some((Player::PlayerTwo, PieceKind::N)),
-                    'r' => Some((Player::PlayerTwo, PieceKind::R)),
-                    'p' => Some((Player::PlayerTwo, PieceKind::P)),
-                    _ => None,
-                })
-            }),
-            eval: 0,
+            current_player: position.player,
+            rows: position.rows,
+            eval,
+            hash: zobrist::hash_grid(&position.rows, position.player),
+            rules: setup.rules,
         }
     }
 
+    pub fn parse_position(s: &str) -> Result<Self, PositionError> {
+        Ok(Self::from_position(&s.parse()?))
+    }
+
+    pub fn to_position(&self) -> String {
+        Position::from_board(self).to_string()
+    }
+
+    fn square_mut(&mut self, square: Square) -> &mut Option<(Player, PieceKind)> {
+        &mut self.rows[square.row() as usize][square.col() as usize]
+    }
+
+    /// Squares cleared by a self-destruct at `pos`.
+    fn blast(&self, pos: Square) -> impl Iterator<Item = Square> {
+        let radius = self.rules.explosion_radius;
+        let rows = pos.row().saturating_sub(radius)..=pos.row().saturating_add(radius).min(8);
+        rows.flat_map(move |row| {
+            let cols = pos.col().saturating_sub(radius)..=pos.col().saturating_add(radius).min(6);
+            cols.filter_map(move |col| Square::new(row, col))
+        })
+    }
+
+    fn may_self_destruct(&self, pos: Square) -> bool {
+        self.rules.self_destruct_own_king
+            || !self
+                .blast(pos)
+                .any(|target| self.get_piece(target) == Some((self.current_player, PieceKind::K)))
+    }
+
+    fn own_pieces(&self) -> impl Iterator<Item = (Square, PieceKind)> + '_ {
+        Square::all().filter_map(|pos| match self.get_piece(pos) {
+            Some((piece_player, kind)) if piece_player == self.current_player => Some((pos, kind)),
+            _ => None,
+        })
+    }
+
+    /// Passes each destination of the piece on `pos` to `push`.
+    fn get_piece_moves(&self, pos: Square, kind: PieceKind, push: &mut impl FnMut(Square)) {
+        let player = &self.current_player;
+        let may_self_destruct = self.may_self_destruct(pos);
+        let push = &mut |nxt: Square| {
+            if nxt != pos || may_self_destruct {
+                push(nxt)
+            }
+        };
+        match kind {
+            PieceKind::B => self.get_bishop_moves(player, pos, push),
+            PieceKind::K => self.get_king_moves(player, pos, push),
+            PieceKind::N => self.get_knight_moves(player, pos, push),
+            PieceKind::P => self.get_pawn_moves(player, pos, push),
+            PieceKind::R => self.get_rook_moves(player, pos, push),
+        }
+    }
+
+    /// Walks from `pos` along `del`, pushing the empty squares if `quiet` and
+    /// the first enemy piece in the way.
     fn raycast_moves(
         &self,
         player: &Player,
-        pos: (u8, u8),
+        pos: Square,
         del: (i8, i8),
-    ) -> (Vec<(u8, u8)>, Option<(u8, u8)>) {
+        quiet: bool,
+        push: &mut impl FnMut(Square),
+    ) {
         let mut cur = pos;
-        let mut ret = vec![];
-        while let Ok(nxt) = check_pos(((cur.0 as i8 + del.0) as u8, (cur.1 as i8 + del.1) as u8)) {
+        while let Some(nxt) = cur.offset(del.0, del.1) {
             cur = nxt;
-            match self.rows[cur.0 as usize][cur.1 as usize] {
-                Some((other_player, _)) if *player == other_player => return (ret, None),
-                Some((_, _)) => {
-                    ret.push(cur);
-                    return (ret, Some(cur));
-                }
-                None => ret.push(cur),
+            match self.get_piece(cur) {
+                Some((other_player, _)) if *player == other_player => return,
+                Some((_, _)) => return push(cur),
+                None if quiet => push(cur),
+                None => {}
             };
         }
-        (ret, None)
     }
 
-    fn get_bishop_moves(&self, player: &Player, pos: (u8, u8)) -> Vec<(u8, u8)> {
-        let mut ret = vec![pos];
-        ret.extend(self.raycast_moves(player, pos, (player.parity() * 1, 1)).0);
-        ret.extend(self.raycast_moves(player, pos, (player.parity() * 1, -1)).0);
-        if let Some(attack_move) = self.raycast_moves(player, pos, (player.parity() * -1, 1)).1 {
-            ret.push(attack_move)
-        }
-        if let Some(attack_move) = self
-            .raycast_moves(player, pos, (player.parity() * -1, -1))
-            .1
-        {
-            ret.push(attack_move)
-        }
-        ret
-    }
-
-    fn get_king_moves(&self, player: &Player, pos: (u8, u8)) -> Vec<(u8, u8)> {
-        let fwd: Vec<(u8, u8)> = [
-            ((pos.0 as i8 + player.parity() * 1) as u8, pos.1),
-            (
-                (pos.0 as i8 + player.parity() * 1) as u8,
-                ((pos.1 as i8) - 1) as u8,
-            ),
-            ((pos.0 as i8 + player.parity() * 1) as u8, pos.1 + 1),
-        ]
-        .iter()
-        .filter(|&nxt| check_pos(*nxt).is_ok())
-        .filter(|&nxt| match self.rows[nxt.0 as usize][nxt.1 as usize] {
-            Some((other_player, _)) if *player == other_player => false,
-            _ => true,
-        })
-        .map(|&a| a)
-        .collect();
-        let mut ret = vec![pos];
-        ret.extend(fwd);
-        ret
-    }
-
-    fn get_pawn_moves(&self, player: &Player, pos: (u8, u8)) -> Vec<(u8, u8)> {
-        let mut ret = vec![pos];
-        if let Ok(nxt) = check_pos((((pos.0 as i8) + player.parity()) as u8, pos.1)) {
-            if self.rows[nxt.0 as usize][nxt.1 as usize].is_none() {
-                ret.push(nxt);
+    fn get_bishop_moves(&self, player: &Player, pos: Square, push: &mut impl FnMut(Square)) {
+        push(pos);
+        self.raycast_moves(player, pos, (player.parity() * 1, 1), true, push);
+        self.raycast_moves(player, pos, (player.parity() * 1, -1), true, push);
+        self.raycast_moves(player, pos, (player.parity() * -1, 1), false, push);
+        self.raycast_moves(player, pos, (player.parity() * -1, -1), false, push);
+    }
+
+    fn get_king_moves(&self, player: &Player, pos: Square, push: &mut impl FnMut(Square)) {
+        push(pos);
+        for del in [0, -1, 1] {
+            if let Some(nxt) = pos.offset(player.parity(), del) {
+                match self.get_piece(nxt) {
+                    Some((other_player, _)) if *player == other_player => {}
+                    _ => push(nxt),
+                }
             }
         }
-        let atk: Vec<(u8, u8)> = [(1, 1), (1, -1)]
-            .iter()
-            .map(|del| {
-                (
-                    (pos.0 as i8 + player.parity() * del.0) as u8,
-                    (pos.1 as i8 + del.1) as u8,
-                )
-            })
-            .filter(|nxt| check_pos(*nxt).is_ok())
-            .filter(|nxt| match self.rows[nxt.0 as usize][nxt.1 as usize] {
-                Some((other_player, _)) if other_player != *player => true,
-                _ => false,
-            })
-            .collect();
-        ret.extend(atk);
-        ret
-    }
-
-    fn get_rook_moves(&self, player: &Player, pos: (u8, u8)) -> Vec<(u8, u8)> {
-        let mut ret = vec![pos];
-        ret.extend(self.raycast_moves(player, pos, (player.parity() * 1, 0)).0);
-        if let Some(attack_move) = self.raycast_moves(player, pos, (0, 1)).1 {
-            ret.push(attack_move)
-        }
-        if let Some(attack_move) = self.raycast_moves(player, pos, (0, -1)).1 {
-            ret.push(attack_move)
+    }
+
+    fn get_pawn_moves(&self, player: &Player, pos: Square, push: &mut impl FnMut(Square)) {
+        push(pos);
+        if let Some(nxt) = pos.offset(player.parity(), 0) {
+            if self.get_piece(nxt).is_none() {
+                push(nxt);
+            }
         }
-        if let Some(attack_move) = self.raycast_moves(player, pos, (player.parity() * -1, 0)).1 {
-            ret.push(attack_move)
+        for del in [(1, 1), (1, -1)] {
+            if let Some(nxt) = pos.offset(player.parity() * del.0, del.1) {
+                match self.get_piece(nxt) {
+                    Some((other_player, _)) if other_player != *player => push(nxt),
+                    _ => {}
+                }
+            }
         }
-        ret
     }
 
-    fn get_knight_moves(&self, player: &Player, pos: (u8, u8)) -> Vec<(u8, u8)> {
-        let fwd: Vec<(u8, u8)> = [(2, 1), (2, -1), (1, 2), (1, -2)]
-            .iter()
-            .map(|del| {
-                (
-                    (pos.0 as i8 + player.parity() * del.0) as u8,
-                    (pos.1 as i8 + del.1) as u8,
-                )
-            })
-            .filter(|nxt| check_pos(*nxt).is_ok())
-            .filter(|nxt| match self.rows[nxt.0 as usize][nxt.1 as usize] {
-                Some((other_player, _)) if other_player == *player => false,
-                _ => true,
-            })
-            .collect();
-
-        let bwd: Vec<(u8, u8)> = [(-2, 1), (-2, -1), (-1, 2), (-1, -2)]
-            .iter()
-            .map(|del| {
-                (
-                    (pos.0 as i8 + player.parity() * del.0) as u8,
-                    (pos.1 as i8 + del.1) as u8,
-                )
-            })
-            .filter(|nxt| check_pos(*nxt).is_ok())
-            .filter(|nxt| match self.rows[nxt.0 as usize][nxt.1 as usize] {
-                Some((other_player, _)) if other_player != *player => true,
-                _ => false,
-            })
-            .collect();
-
-        let mut ret = vec![pos];
-        ret.extend(fwd);
-        ret.extend(bwd);
-        ret
+    fn get_rook_moves(&self, player: &Player, pos: Square, push: &mut impl FnMut(Square)) {
+        push(pos);
+        self.raycast_moves(player, pos, (player.parity() * 1, 0), true, push);
+        self.raycast_moves(player, pos, (0, 1), false, push);
+        self.raycast_moves(player, pos, (0, -1), false, push);
+        self.raycast_moves(player, pos, (player.parity() * -1, 0), false, push);
     }
-}
 
-fn check_pos(pos: (u8, u8)) -> Result<(u8, u8), MoveError> {
-    if pos.0 >= 9 || pos.1 >= 7 {
-        return Err(MoveError::InvalidPosition);
+    fn get_knight_moves(&self, player: &Player, pos: Square, push: &mut impl FnMut(Square)) {
+        push(pos);
+        for del in [(2, 1), (2, -1), (1, 2), (1, -2)] {
+            if let Some(nxt) = pos.offset(player.parity() * del.0, del.1) {
+                match self.get_piece(nxt) {
+                    Some((other_player, _)) if other_player == *player => {}
+                    _ => push(nxt),
+                }
+            }
+        }
+        for del in [(-2, 1), (-2, -1), (-1, 2), (-1, -2)] {
+            if let Some(nxt) = pos.offset(player.parity() * del.0, del.1) {
+                match self.get_piece(nxt) {
+                    Some((other_player, _)) if other_player != *player => push(nxt),
+                    _ => {}
+                }
+            }
+        }
     }
-    Ok(pos)
 }
 
 impl Board<SimpleMove> for SimpleBoard {
-    fn get_piece(&self, row: u8, col: u8) -> Option<(Player, PieceKind)> {
-        self.rows[row as usize][col as usize]
+    fn get_piece(&self, square: Square) -> Option<(Player, PieceKind)> {
+        self.rows[square.row() as usize][square.col() as usize]
     }
 
     fn get_player(&self) -> Player {
         self.current_player
     }
 
+    fn get_rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
     fn get_moves(&self) -> Vec<SimpleMove> {
-        self.rows
-            .iter()
-            .flatten()
-            .enumerate()
-            .filter(|(_, &piece)| match piece {
-                Some((piece_player, _)) if piece_player == self.current_player => true,
-                _ => false,
-            })
-            .map(|(pos, piece)| (((pos / 7) as u8, (pos % 7) as u8), piece))
-            .map(|(pos, piece)| match piece {
-                Some((player, kind)) => match kind {
-                    PieceKind::B => self.get_bishop_moves(player, pos),
-                    PieceKind::K => self.get_king_moves(player, pos),
-                    PieceKind::N => self.get_knight_moves(player, pos),
-                    PieceKind::P => self.get_pawn_moves(player, pos),
-                    PieceKind::R => self.get_rook_moves(player, pos),
-                    // _ => vec![],
-                }
-                .iter()
-                .map(|&nxt| SimpleMove {
-                    from_rc: pos,
-                    dest_rc: nxt,
+        let mut moves = MoveList::new();
+        self.get_moves_into(&mut moves);
+        moves.to_vec()
+    }
+
+    fn get_moves_into(&self, moves: &mut MoveList<SimpleMove>) {
+        for (pos, piece) in self.own_pieces() {
+            self.get_piece_moves(pos, piece, &mut |nxt| {
+                moves.push(SimpleMove {
+                    from: pos,
+                    dest: nxt,
                 })
-                .collect(),
-                _ => vec![],
-            })
-            .flatten()
-            .collect::<Vec<SimpleMove>>()
+            });
+        }
+    }
+
+    fn get_moves_from(&self, square: Square) -> Vec<SimpleMove> {
+        let mut moves = vec![];
+        if let Some((player, kind)) = self.get_piece(square) {
+            if player == self.current_player {
+                self.get_piece_moves(square, kind, &mut |dest| {
+                    moves.push(SimpleMove { from: square, dest })
+                });
+            }
+        }
+        moves
+    }
+
+    fn has_any_move(&self) -> bool {
+        self.own_pieces().any(|(pos, piece)| {
+            let mut any = false;
+            self.get_piece_moves(pos, piece, &mut |_| any = true);
+            any
+        })
     }
 
     fn get_winner(&self) -> Option<Player> {
@@ -332,61 +357,132 @@ impl Board<SimpleMove> for SimpleBoard {
         }) {
             return Some(Player::PlayerOne);
         }
-        if self.get_moves().is_empty() {
+        if !self.has_any_move() {
             return Some(self.current_player.other());
         }
         None
     }
 
+    fn material(&self) -> i32 {
+        self.eval
+    }
+
     fn do_move(&mut self, mov: &SimpleMove) {
-        check_pos(mov.from_rc).unwrap();
-        check_pos(mov.dest_rc).unwrap();
-        if mov.from_rc == mov.dest_rc {
+        if mov.from == mov.dest {
             // Explode!!
-            for clear_row in [
-                (mov.from_rc.0 as i8 - 1) as u8,
-                mov.from_rc.0,
-                mov.from_rc.0 + 1,
-            ] {
-                for clear_col in [
-                    (mov.from_rc.1 as i8 - 1) as u8,
-                    mov.from_rc.1,
-                    mov.from_rc.1 + 1,
-                ] {
-                    if check_pos((clear_row, clear_col)).is_ok() {
-                        if let Some((old_player, old_kind)) =
-                            self.rows[clear_row as usize][clear_col as usize]
-                        {
-                            self.eval -=
-                                (old_player.parity() as i32) * (piece_rank(&old_kind) as i32);
-                        }
-                        self.rows[clear_row as usize][clear_col as usize] = None;
-                    }
+            for clear in self.blast(mov.from) {
+                if let Some((old_player, old_kind)) = self.get_piece(clear) {
+                    self.eval -= (old_player.parity() as i32) * old_kind.value();
+                    self.hash ^= zobrist::piece_key(old_player, old_kind, clear.index());
                 }
+                *self.square_mut(clear) = None;
             }
             self.current_player = self.current_player.other();
+            self.hash ^= zobrist::PLAYER_TWO_KEY;
             return;
         }
         // don't validate. w/e.
-        if let Some((old_player, old_kind)) =
-            self.rows[mov.dest_rc.0 as usize][mov.dest_rc.1 as usize]
-        {
-            self.eval -= (old_player.parity() as i32) * (piece_rank(&old_kind) as i32);
+        let from_pos = mov.from.index();
+        let dest_pos = mov.dest.index();
+        if let Some((old_player, old_kind)) = self.get_piece(mov.dest) {
+            self.eval -= (old_player.parity() as i32) * old_kind.value();
+            self.hash ^= zobrist::piece_key(old_player, old_kind, dest_pos);
+        }
+        if let Some((player, kind)) = self.get_piece(mov.from) {
+            self.hash ^= zobrist::piece_key(player, kind, from_pos);
+            self.hash ^= zobrist::piece_key(player, kind, dest_pos);
+        }
+        *self.square_mut(mov.dest) = self.get_piece(mov.from);
+        *self.square_mut(mov.from) = None;
+        self.current_player = self.current_player.other();
+        self.hash ^= zobrist::PLAYER_TWO_KEY;
+    }
+
+    fn make_move(&mut self, mov: &SimpleMove) -> Undo {
+        let mut undo = Undo::default();
+        if mov.from == mov.dest {
+            for target in self.blast(mov.from) {
+                if let Some((player, kind)) = self.get_piece(target) {
+                    undo.push(target.index(), player, kind);
+                }
+            }
+        } else if let Some((player, kind)) = self.get_piece(mov.dest) {
+            undo.push(mov.dest.index(), player, kind);
         }
-        self.rows[mov.dest_rc.0 as usize][mov.dest_rc.1 as usize] =
-            self.rows[mov.from_rc.0 as usize][mov.from_rc.1 as usize];
-        self.rows[mov.from_rc.0 as usize][mov.from_rc.1 as usize] = None;
+        self.do_move(mov);
+        undo
+    }
+
+    fn undo_move(&mut self, mov: &SimpleMove, undo: &Undo) {
         self.current_player = self.current_player.other();
+        self.hash ^= zobrist::PLAYER_TWO_KEY;
+        if mov.from != mov.dest {
+            if let Some((player, kind)) = self.get_piece(mov.dest) {
+                self.hash ^= zobrist::piece_key(player, kind, mov.dest.index());
+                self.hash ^= zobrist::piece_key(player, kind, mov.from.index());
+            }
+            *self.square_mut(mov.from) = self.get_piece(mov.dest);
+            *self.square_mut(mov.dest) = None;
+        }
+        for (pos, player, kind) in undo.removed() {
+            *self.square_mut(Square::from_index_unchecked(pos)) = Some((player, kind));
+            self.eval += (player.parity() as i32) * kind.value();
+            self.hash ^= zobrist::piece_key(player, kind, pos);
+        }
     }
 
     fn invert(&self) -> Self {
-        todo!()
+        let mut rows = self.rows;
+        rows.reverse();
+        let rows =
+            rows.map(|row| row.map(|piece| piece.map(|(player, kind)| (player.other(), kind))));
+        let current_player = self.current_player.other();
+        SimpleBoard {
+            current_player,
+            rows,
+            eval: -self.eval,
+            hash: zobrist::hash_grid(&rows, current_player),
+            rules: self.rules,
+        }
+    }
+
+    fn mirror_horizontal(&self) -> Self {
+        let rows = self.rows.map(|mut row| {
+            row.reverse();
+            row
+        });
+        SimpleBoard {
+            rows,
+            hash: zobrist::hash_grid(&rows, self.current_player),
+            ..*self
+        }
     }
 }
 
 mod test {
     use super::*;
 
+    #[test]
+    fn parse_moves() {
+        for mov in SimpleBoard::init().get_moves() {
+            assert_eq!(mov.to_string().parse::<SimpleMove>(), Ok(mov));
+            assert_eq!(
+                mov.to_string().to_lowercase().parse::<SimpleMove>(),
+                Ok(mov)
+            );
+        }
+        assert_eq!(
+            "H2A3".parse::<SimpleMove>(),
+            Err(MoveError::InvalidPosition)
+        );
+        assert_eq!(
+            "D0E3".parse::<SimpleMove>(),
+            Err(MoveError::InvalidPosition)
+        );
+        assert_eq!("D2E".parse::<SimpleMove>(), Err(MoveError::InvalidMove));
+        assert_eq!("D2E3F4".parse::<SimpleMove>(), Err(MoveError::InvalidMove));
+    }
+
     #[test]
     fn opening_board_moves() {
         let board = SimpleBoard::init();
@@ -0,0 +1,173 @@
+//! Squares of the 7x9 board.
+//!
+//! A square is stored as its index `7 * row + col`, the numbering `BitBoard`
+//! masks use. Rows count up from rank 1 and columns from the A file, so
+//! `Square::new(1, 3)` is `D2`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::board::MoveError;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Square(u8);
+
+impl Square {
+    pub const ROWS: u8 = 9;
+    pub const COLS: u8 = 7;
+    pub const COUNT: u8 = Self::ROWS * Self::COLS;
+
+    pub const fn new(row: u8, col: u8) -> Option<Square> {
+        match row < Self::ROWS && col < Self::COLS {
+            true => Some(Square(Self::COLS * row + col)),
+            false => None,
+        }
+    }
+
+    pub const fn from_index(index: u8) -> Option<Square> {
+        match index < Self::COUNT {
+            true => Some(Square(index)),
+            false => None,
+        }
+    }
+
+    /// `from_index` for indices already known to be on the board, such as the
+    /// set bits of a mask.
+    pub(crate) const fn from_index_unchecked(index: u8) -> Square {
+        debug_assert!(index < Self::COUNT);
+        Square(index)
+    }
+
+    /// Parses a file letter in either case and a rank digit, such as `d2`.
+    pub(crate) fn from_chars(file: u8, rank: u8) -> Result<Square, MoveError> {
+        match (file.to_ascii_uppercase(), rank) {
+            (b'A'..=b'G', b'1'..=b'9') => Ok(Square(
+                Self::COLS * (rank - b'1') + (file.to_ascii_uppercase() - b'A'),
+            )),
+            _ => Err(MoveError::InvalidPosition),
+        }
+    }
+
+    /// Every square, in index order.
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..Self::COUNT).map(Square)
+    }
+
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+
+    pub const fn row(self) -> u8 {
+        self.0 / Self::COLS
+    }
+
+    pub const fn col(self) -> u8 {
+        self.0 % Self::COLS
+    }
+
+    /// `'A'` to `'G'`.
+    pub const fn file(self) -> char {
+        (b'A' + self.col()) as char
+    }
+
+    /// 1 to 9.
+    pub const fn rank(self) -> u8 {
+        self.row() + 1
+    }
+
+    pub const fn bit(self) -> u64 {
+        1 << self.0
+    }
+
+    /// The square `rows` up and `cols` to the right, if it is on the board.
+    pub fn offset(self, rows: i8, cols: i8) -> Option<Square> {
+        let row = self.row().checked_add_signed(rows)?;
+        let col = self.col().checked_add_signed(cols)?;
+        Square::new(row, col)
+    }
+
+    /// The square as seen from the other side: the rank is reflected.
+    pub const fn invert(self) -> Square {
+        Square(Self::COLS * (Self::ROWS - 1 - self.row()) + self.col())
+    }
+
+    /// The square reflected across the D file.
+    pub const fn mirror_horizontal(self) -> Square {
+        Square(Self::COLS * self.row() + Self::COLS - 1 - self.col())
+    }
+
+    /// King-step distance: the larger of the row and column differences.
+    pub const fn distance(self, other: Square) -> u8 {
+        let rows = self.row().abs_diff(other.row());
+        let cols = self.col().abs_diff(other.col());
+        match rows > cols {
+            true => rows,
+            false => cols,
+        }
+    }
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+impl FromStr for Square {
+    type Err = MoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().as_bytes() {
+            [file, rank] => Square::from_chars(*file, *rank),
+            _ => Err(MoveError::InvalidPosition),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coordinates() {
+        let d2 = Square::new(1, 3).unwrap();
+        assert_eq!((d2.index(), d2.row(), d2.col()), (10, 1, 3));
+        assert_eq!((d2.file(), d2.rank()), ('D', 2));
+        assert_eq!(d2.bit(), 1 << 10);
+        assert_eq!(Square::from_index(10), Some(d2));
+        assert_eq!(Square::new(9, 0), None);
+        assert_eq!(Square::new(0, 7), None);
+        assert_eq!(Square::from_index(63), None);
+        assert_eq!(Square::all().count(), 63);
+        assert!(Square::all()
+            .enumerate()
+            .all(|(idx, square)| square.index() as usize == idx));
+    }
+
+    #[test]
+    fn names() {
+        for square in Square::all() {
+            assert_eq!(square.to_string().parse(), Ok(square));
+            assert_eq!(square.to_string().to_lowercase().parse(), Ok(square));
+        }
+        assert_eq!(Square::new(8, 6).unwrap().to_string(), "G9");
+        assert_eq!("H1".parse::<Square>(), Err(MoveError::InvalidPosition));
+        assert_eq!("A0".parse::<Square>(), Err(MoveError::InvalidPosition));
+        assert_eq!("A10".parse::<Square>(), Err(MoveError::InvalidPosition));
+    }
+
+    #[test]
+    fn offsets() {
+        let a1: Square = "A1".parse().unwrap();
+        assert_eq!(a1.offset(2, 1), "B3".parse().ok());
+        assert_eq!(a1.offset(-1, 0), None);
+        assert_eq!(a1.offset(0, -1), None);
+        assert_eq!("G9".parse::<Square>().unwrap().offset(1, 0), None);
+        assert_eq!("G9".parse::<Square>().unwrap().offset(0, 1), None);
+        let b3: Square = "B3".parse().unwrap();
+        assert_eq!(b3.invert().to_string(), "B7");
+        assert_eq!(b3.mirror_horizontal().to_string(), "F3");
+        assert_eq!(b3.distance(a1), 2);
+        assert_eq!(b3.distance("F4".parse().unwrap()), 4);
+    }
+}
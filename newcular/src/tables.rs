@@ -0,0 +1,149 @@
+//! Attack tables for `BitBoard`, indexed by square (`7 * row + col`) and
+//! computed at compile time.
+
+/// Ray directions as (row, col) steps. The first four run towards higher
+/// square indices, the last four towards lower ones.
+const DIRECTIONS: [(i8, i8); 8] = [
+    (1, 0),
+    (0, 1),
+    (1, 1),
+    (1, -1),
+    (-1, 0),
+    (0, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+pub(crate) const NORTH: usize = 0;
+pub(crate) const EAST: usize = 1;
+pub(crate) const NORTH_EAST: usize = 2;
+pub(crate) const NORTH_WEST: usize = 3;
+pub(crate) const SOUTH: usize = 4;
+pub(crate) const WEST: usize = 5;
+pub(crate) const SOUTH_EAST: usize = 6;
+pub(crate) const SOUTH_WEST: usize = 7;
+
+const fn square_mask(row: i8, col: i8) -> u64 {
+    match row >= 0 && row < 9 && col >= 0 && col < 7 {
+        true => 1u64 << (7 * row + col),
+        false => 0,
+    }
+}
+
+const fn build_rays() -> [[u64; 63]; 8] {
+    let mut rays = [[0u64; 63]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut pos = 0;
+        while pos < 63 {
+            let (mut row, mut col) = ((pos / 7) as i8, (pos % 7) as i8);
+            loop {
+                row += DIRECTIONS[dir].0;
+                col += DIRECTIONS[dir].1;
+                let mask = square_mask(row, col);
+                if mask == 0 {
+                    break;
+                }
+                rays[dir][pos] |= mask;
+            }
+            pos += 1;
+        }
+        dir += 1;
+    }
+    rays
+}
+
+/// Squares reached from each square by the given (row, col) steps.
+const fn build_jumps(steps: &[(i8, i8)]) -> [u64; 63] {
+    let mut jumps = [0u64; 63];
+    let mut pos = 0;
+    while pos < 63 {
+        let (row, col) = ((pos / 7) as i8, (pos % 7) as i8);
+        let mut step = 0;
+        while step < steps.len() {
+            jumps[pos] |= square_mask(row + steps[step].0, col + steps[step].1);
+            step += 1;
+        }
+        pos += 1;
+    }
+    jumps
+}
+
+/// Piece-square bonus in tenths of a pawn for a piece of each kind (indexed
+/// in `PieceKind` order) on each square, seen from its owner's side: row 0 is
+/// the owner's back rank. Every row is symmetric about the D file.
+const fn build_piece_square() -> [[i32; 63]; 5] {
+    let mut table = [[0i32; 63]; 5];
+    let mut pos = 0;
+    while pos < 63 {
+        let (row, col) = ((pos / 7) as i32, (pos % 7) as i32);
+        let centre_col = 3 - (col - 3).abs();
+        let centre_row = 4 - (row - 4).abs();
+        // Bishop, king, knight, rook, pawn.
+        table[0][pos] = centre_col + centre_row - 3;
+        table[1][pos] = -2 * row;
+        table[2][pos] = 2 * centre_col + centre_row - 4;
+        table[3][pos] = 0;
+        table[4][pos] = 2 * row - 6;
+        pos += 1;
+    }
+    table
+}
+
+pub(crate) static PIECE_SQUARE: [[i32; 63]; 5] = build_piece_square();
+
+static RAYS: [[u64; 63]; 8] = build_rays();
+
+/// Knight targets on higher and lower ranks.
+pub(crate) static KNIGHT_UP: [u64; 63] = build_jumps(&[(2, 1), (2, -1), (1, 2), (1, -2)]);
+pub(crate) static KNIGHT_DOWN: [u64; 63] = build_jumps(&[(-2, 1), (-2, -1), (-1, 2), (-1, -2)]);
+
+/// The three squares on the next rank up and down: king steps, and pawn
+/// captures once the square straight ahead is removed.
+pub(crate) static KING_UP: [u64; 63] = build_jumps(&[(1, -1), (1, 0), (1, 1)]);
+pub(crate) static KING_DOWN: [u64; 63] = build_jumps(&[(-1, -1), (-1, 0), (-1, 1)]);
+
+/// Squares along `dir` from `pos` up to and including the first occupied one.
+#[inline]
+pub(crate) fn ray(dir: usize, pos: u8, occupied: u64) -> u64 {
+    let ray = RAYS[dir][pos as usize];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        return ray;
+    }
+    let first = match dir < SOUTH {
+        true => blockers.trailing_zeros(),
+        false => 63 - blockers.leading_zeros(),
+    };
+    ray ^ RAYS[dir][first as usize]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table_shapes() {
+        // D5 is square 31 in the middle of the board.
+        assert_eq!(RAYS[NORTH][31].count_ones(), 4);
+        assert_eq!(RAYS[SOUTH][31].count_ones(), 4);
+        assert_eq!(RAYS[EAST][31].count_ones(), 3);
+        assert_eq!(RAYS[NORTH_WEST][31].count_ones(), 3);
+        assert_eq!(KNIGHT_UP[31].count_ones() + KNIGHT_DOWN[31].count_ones(), 8);
+        assert_eq!(KNIGHT_UP[0], (1 << 15) | (1 << 9));
+        assert_eq!(KING_UP[0], (1 << 7) | (1 << 8));
+        assert_eq!(KING_DOWN[0], 0);
+        assert_eq!(
+            RAYS.iter().flatten().fold(0, |all, ray| all | ray),
+            (1 << 63) - 1
+        );
+    }
+
+    #[test]
+    fn ray_stops_at_first_blocker() {
+        let occupied = (1 << 45) | (1 << 52) | (1 << 17);
+        assert_eq!(ray(NORTH, 31, occupied), (1 << 38) | (1 << 45));
+        assert_eq!(ray(SOUTH, 31, occupied), (1 << 24) | (1 << 17));
+        assert_eq!(ray(WEST, 31, occupied), (1 << 30) | (1 << 29) | (1 << 28));
+    }
+}
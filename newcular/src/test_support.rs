@@ -0,0 +1,12 @@
+//! Fixtures shared by the unit tests of several modules.
+
+use crate::board::Square;
+
+/// PlayerTwo to move, with its king on E8 beside a PlayerOne bishop and its
+/// rook on C4 able to take the E4 pawn, so explosions, captures and quiet moves
+/// are all on offer.
+pub const KING_BY_BISHOP: &str = "1n2rn1/3Bk2/7/7/7/2r1P1P/2RB2N/3KR2/7 2";
+
+pub fn sq(name: &str) -> Square {
+    name.parse().unwrap()
+}
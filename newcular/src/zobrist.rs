@@ -0,0 +1,121 @@
+//! Zobrist keys shared by every board implementation, so the same position
+//! hashes to the same value whichever backend produced it.
+
+use crate::board::*;
+use crate::position::Grid;
+
+pub(crate) const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn build_piece_keys() -> [[u64; 63]; 10] {
+    let mut keys = [[0u64; 63]; 10];
+    let mut state = 0x6e6577637560u64;
+    let mut piece = 0;
+    while piece < 10 {
+        let mut pos = 0;
+        while pos < 63 {
+            let (next_state, key) = splitmix64(state);
+            state = next_state;
+            keys[piece][pos] = key;
+            pos += 1;
+        }
+        piece += 1;
+    }
+    keys
+}
+
+static PIECE_KEYS: [[u64; 63]; 10] = build_piece_keys();
+
+/// Toggled whenever the side to move changes; present when PlayerTwo is to move.
+pub const PLAYER_TWO_KEY: u64 = splitmix64(0x706c617965723221).1;
+
+/// Key for a piece standing on square `pos` (`7 * row + col`).
+#[inline]
+pub fn piece_key(player: Player, kind: PieceKind, pos: u8) -> u64 {
+    let kind_idx = match kind {
+        PieceKind::B => 0,
+        PieceKind::K => 1,
+        PieceKind::N => 2,
+        PieceKind::R => 3,
+        PieceKind::P => 4,
+    };
+    let player_idx = match player {
+        Player::PlayerOne => 0,
+        Player::PlayerTwo => 5,
+    };
+    PIECE_KEYS[kind_idx + player_idx][pos as usize]
+}
+
+#[inline]
+pub fn player_key(player: Player) -> u64 {
+    match player {
+        Player::PlayerOne => 0,
+        Player::PlayerTwo => PLAYER_TWO_KEY,
+    }
+}
+
+pub fn hash_grid(rows: &Grid, player: Player) -> u64 {
+    rows.iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(pos, piece)| piece.map(|(owner, kind)| piece_key(owner, kind, pos as u8)))
+        .fold(player_key(player), |hash, key| hash ^ key)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitboard::{BitBoard, BitBoardMove};
+    use crate::position::Position;
+    use crate::simple::SimpleBoard;
+    use rand::prelude::*;
+
+    #[test]
+    fn keys_are_distinct() {
+        let mut keys: Vec<u64> = PIECE_KEYS.iter().flatten().copied().collect();
+        keys.push(PLAYER_TWO_KEY);
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), 10 * 63 + 1);
+    }
+
+    #[test]
+    fn side_to_move_changes_hash() {
+        let one =
+            SimpleBoard::parse_position("1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 1");
+        let two =
+            SimpleBoard::parse_position("1nrkrn1/3b3/3b3/p1p1p1p/7/P1P1P1P/3B3/3B3/1NRKRN1 2");
+        assert_eq!(
+            one.unwrap().get_hash() ^ two.unwrap().get_hash(),
+            PLAYER_TWO_KEY
+        );
+    }
+
+    #[test]
+    fn incremental_hash_matches_recomputed() {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..200 {
+            let mut simple = SimpleBoard::init();
+            let mut bitboard = BitBoard::init();
+
+            while simple.get_winner().is_none() {
+                let position = Position::from_board(&simple);
+                let expected = hash_grid(&position.rows, position.player);
+                assert_eq!(simple.get_hash(), expected);
+                assert_eq!(bitboard.get_hash(), expected);
+                assert_eq!(simple.invert().get_hash(), bitboard.invert().get_hash());
+
+                let picked_move = *simple.get_moves().choose(&mut rng).unwrap();
+                simple.do_move(&picked_move);
+                let (from, dest) = picked_move.get_from_dest();
+                bitboard.do_move(&BitBoardMove::from_from_dest(from, dest));
+            }
+        }
+    }
+}